@@ -0,0 +1,31 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use blakeout::{Blakeout, to_hex};
+
+const DATA: &[u8; 29] = b"Science is poetry of reality!";
+
+/// Run with and without `prefetch` and `simd` features to see their effect
+fn hash(c: &mut Criterion) {
+    let mut digest = Blakeout::default();
+    c.bench_function("hash default", |b| b.iter(|| {
+        digest.reset();
+        digest.update(DATA);
+        digest.compute();
+    }));
+}
+
+/// Small scratchpad on a fresh hasher, so the cost of allocations is noticeable
+fn hash_fresh(c: &mut Criterion) {
+    c.bench_function("hash fresh small", |b| b.iter(|| {
+        let mut digest = Blakeout::with_params(32, 16).unwrap();
+        digest.update(DATA);
+        digest.finalize()
+    }));
+}
+
+fn hex(c: &mut Criterion) {
+    let bytes: Vec<u8> = (0..=255).cycle().take(4096).collect();
+    c.bench_function("to_hex 4 KB", |b| b.iter(|| to_hex(&bytes)));
+}
+
+criterion_group!(benches, hash, hash_fresh, hex);
+criterion_main!(benches);
@@ -1,10 +1,6 @@
 use std::env;
-use std::fs;
 use std::io::{self, Read};
-use blakeout::Blakeout;
-use digest::Digest;
-
-const BUFFER_SIZE: usize = 1024;
+use blakeout::{Blakeout, hash_file_hex};
 
 /// Print digest result as hex string and name pair
 fn print_result(sum: &str, name: &str) {
@@ -13,20 +9,12 @@ fn print_result(sum: &str, name: &str) {
 
 /// Compute digest value for given `Reader` and print it
 /// On any error simply return without doing anything
-fn process<D: Digest + Default, R: Read>(reader: &mut R, name: &str) {
-    let mut sh = D::default();
-    let mut buffer = [0u8; BUFFER_SIZE];
-    loop {
-        let n = match reader.read(&mut buffer) {
-            Ok(n) => n,
-            Err(_) => return,
-        };
-        sh.input(&buffer[..n]);
-        if n == 0 || n < BUFFER_SIZE {
-            break;
-        }
+fn process<R: Read>(reader: &mut R, name: &str) {
+    let mut sh = Blakeout::new();
+    if sh.update_from_reader(reader).is_err() {
+        return;
     }
-
+    sh.compute();
     print_result(&sh.result_str(), name);
 }
 
@@ -36,11 +24,11 @@ fn main() {
     // If no files provided process input from stdin
     if args.len() > 1 {
         for path in args.skip(1) {
-            if let Ok(mut file) = fs::File::open(&path) {
-                process::<Blakeout, _>(&mut file, &path);
+            if let Ok(sum) = hash_file_hex(&path) {
+                print_result(&sum, &path);
             }
         }
     } else {
-        process::<Blakeout, _>(&mut io::stdin(), "-");
+        process(&mut io::stdin(), "-");
     }
-}
\ No newline at end of file
+}
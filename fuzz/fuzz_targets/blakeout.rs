@@ -0,0 +1,47 @@
+//! Run by `cargo +nightly fuzz run blakeout`
+#![no_main]
+
+use blakeout::Blakeout;
+use libfuzzer_sys::fuzz_target;
+
+/// Small scratchpad to make many runs per second, the code paths are the same
+const HASH_COUNT: usize = 64;
+
+fuzz_target!(|data: &[u8]| {
+    // The first byte selects the size of updates, the rest is the message
+    let (step, message) = match data.split_first() {
+        Some((step, message)) => (*step as usize + 1, message),
+        None => (1, data),
+    };
+    let mut one_shot = Blakeout::with_params(32, HASH_COUNT).unwrap();
+    one_shot.update(message);
+    one_shot.compute();
+
+    let mut streaming = Blakeout::with_params(32, HASH_COUNT).unwrap();
+    streaming.update(b"garbage to reset");
+    streaming.reset();
+    for chunk in message.chunks(step) {
+        streaming.update(chunk);
+    }
+    streaming.compute();
+    assert_eq!(one_shot.result(), streaming.result());
+
+    let mut all = Blakeout::with_params(32, HASH_COUNT).unwrap();
+    all.update_all(message.chunks(step));
+    all.compute();
+    assert_eq!(one_shot.result(), all.result());
+
+    // Chained updates don't accumulate, each one folds the previous result
+    let mut chained = Blakeout::with_params(32, HASH_COUNT).unwrap();
+    let mut expected = Blakeout::with_params(32, HASH_COUNT).unwrap();
+    let mut previous: Vec<u8> = Vec::new();
+    for chunk in message.chunks(step) {
+        chained.update_chained(chunk);
+        expected.reset();
+        expected.update([&previous[..], chunk].concat());
+        expected.compute();
+        previous = expected.result().to_vec();
+        assert_eq!(expected.result(), chained.result());
+    }
+    assert_eq!(streaming.finalize(), one_shot.finalize());
+});
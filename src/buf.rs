@@ -0,0 +1,54 @@
+use bytes::Buf;
+
+use crate::{Blakeout, Error, InnerDigest};
+
+impl<D: InnerDigest> Blakeout<D> {
+    /// Appends all remaining bytes of `buf` to the message.
+    ///
+    /// The chunks of `buf` are copied to the message as they are,
+    /// without collecting them to a contiguous buffer first.
+    /// Panics if the message would be longer than `BlakeoutBuilder::max_input`.
+    pub fn update_buf(&mut self, buf: impl Buf) {
+        self.try_update_buf(buf).expect("message is longer than max_input");
+    }
+
+    /// Same as `update_buf`, but returns `Error::InputTooLarge` instead of panicking,
+    /// the message and `buf` are left unchanged in that case
+    pub fn try_update_buf(&mut self, mut buf: impl Buf) -> Result<(), Error> {
+        self.check_input(buf.remaining())?;
+        while buf.has_remaining() {
+            let chunk = buf.chunk();
+            let len = chunk.len();
+            self.append(chunk);
+            buf.advance(len);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{Buf, Bytes};
+    use crate::{Blakeout, BlakeoutBuilder, Error};
+    const DATA: &[u8; 29] = b"Science is poetry of reality!";
+
+    #[test]
+    fn chained_buf() {
+        let buf = Bytes::from_static(&DATA[..10]).chain(Bytes::from_static(&DATA[10..]));
+        assert_eq!(10, buf.chunk().len());
+        let mut digest = Blakeout::new();
+        digest.update_buf(buf);
+        digest.compute();
+        assert_eq!(Blakeout::digest(DATA), digest.finalize());
+
+        let mut empty = Blakeout::new();
+        empty.update_buf(Bytes::new());
+        assert_eq!(Blakeout::digest(b""), empty.finalize());
+
+        let mut limited = BlakeoutBuilder::new().max_input(DATA.len() - 1).build().unwrap();
+        let mut buf = Bytes::from_static(DATA);
+        assert_eq!(Err(Error::InputTooLarge), limited.try_update_buf(&mut buf));
+        assert_eq!(DATA.len(), buf.remaining());
+        assert!(limited.input.is_empty());
+    }
+}
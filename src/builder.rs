@@ -0,0 +1,242 @@
+use alloc::vec::Vec;
+
+use crate::{Blakeout, BlakeoutMode, DEFAULT_HASH_COUNT, DEFAULT_LOOKBACK_BLOCKS, Error, InnerDigest, MIN_HASH_COUNT, VarBlake2s, buffer_size};
+
+/// Builder for hashers with custom parameters, key, salt, personalization and domain.
+///
+/// ```rust
+/// use blakeout::BlakeoutBuilder;
+///
+/// let mut hasher = BlakeoutBuilder::new()
+///     .hash_count(1024)
+///     .personal(b"MyApp")
+///     .build()
+///     .unwrap();
+/// hasher.update(b"hello world");
+///
+/// // The same with Blake2b as inner hash
+/// let mut hasher = BlakeoutBuilder::new()
+///     .hash_count(1024)
+///     .build_inner::<blakeout::VarBlake2b>()
+///     .unwrap();
+/// hasher.update(b"hello world");
+/// assert_eq!(64, hasher.compute().len());
+/// ```
+#[derive(Clone)]
+pub struct BlakeoutBuilder {
+    hash_size: Option<usize>,
+    hash_count: usize,
+    lookback_blocks: usize,
+    mode: BlakeoutMode,
+    max_input: usize,
+    key: Vec<u8>,
+    salt: Vec<u8>,
+    personal: Vec<u8>,
+    domain: Vec<u8>,
+}
+
+impl Default for BlakeoutBuilder {
+    fn default() -> Self {
+        BlakeoutBuilder::new()
+    }
+}
+
+impl BlakeoutBuilder {
+    /// Creates new builder with default parameters
+    pub fn new() -> Self {
+        BlakeoutBuilder {
+            hash_size: None,
+            hash_count: DEFAULT_HASH_COUNT,
+            lookback_blocks: DEFAULT_LOOKBACK_BLOCKS,
+            mode: BlakeoutMode::DualPass,
+            max_input: usize::MAX,
+            key: Vec::new(),
+            salt: Vec::new(),
+            personal: Vec::new(),
+            domain: Vec::new(),
+        }
+    }
+
+    /// Sets the size of scratchpad hashes and the result
+    /// (1..=32 bytes for Blake2s, the default is the maximum)
+    pub fn hash_size(mut self, hash_size: usize) -> Self {
+        self.hash_size = Some(hash_size);
+        self
+    }
+
+    /// Sets the number of hashes in the scratchpad (at least 2)
+    pub fn hash_count(mut self, hash_count: usize) -> Self {
+        self.hash_count = hash_count;
+        self
+    }
+
+    /// Sets how many previous scratchpad hashes are hashed to get every next one (at least 1).
+    ///
+    /// The default is 2, as in the original algorithm, any other value changes the results.
+    /// Larger windows make every hash depend on more memory, but take more time to hash.
+    pub fn lookback_blocks(mut self, lookback_blocks: usize) -> Self {
+        self.lookback_blocks = lookback_blocks;
+        self
+    }
+
+    /// Sets the passes over the filled scratchpad, the default is `BlakeoutMode::DualPass`.
+    ///
+    /// `BlakeoutMode::ForwardOnly` is faster, but it changes the results and makes
+    /// the hash not memory hard, see its documentation.
+    pub fn mode(mut self, mode: BlakeoutMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Limits the length of the message in bytes, there is no limit by default.
+    ///
+    /// The whole message is kept in memory until it is hashed,
+    /// so untrusted input should be limited. Appending beyond the limit by `try_update`
+    /// and other `try_` methods fails with `Error::InputTooLarge` and leaves the message
+    /// unchanged, while `update` and other infallible methods panic.
+    /// The limit is not saved by `save_state`.
+    pub fn max_input(mut self, max_input: usize) -> Self {
+        self.max_input = max_input;
+        self
+    }
+
+    /// Sets the key for MAC mode (at most 32 bytes for Blake2s)
+    pub fn key(mut self, key: &[u8]) -> Self {
+        self.key = key.to_vec();
+        self
+    }
+
+    /// Sets the salt (at most 8 bytes for Blake2s, padded with zeroes)
+    pub fn salt(mut self, salt: &[u8]) -> Self {
+        self.salt = salt.to_vec();
+        self
+    }
+
+    /// Sets the personalization (at most 8 bytes for Blake2s, padded with zeroes)
+    pub fn personal(mut self, personal: &[u8]) -> Self {
+        self.personal = personal.to_vec();
+        self
+    }
+
+    /// Sets the domain to separate results of different uses, see `Blakeout::new_with_domain`
+    pub fn domain(mut self, domain: &[u8]) -> Self {
+        self.domain = domain.to_vec();
+        self
+    }
+
+    /// Validates the parameters and creates the hasher
+    pub fn build(self) -> Result<Blakeout, Error> {
+        self.build_inner::<VarBlake2s>()
+    }
+
+    /// Validates the parameters and creates the hasher with inner hash function `D`
+    pub fn build_inner<D: InnerDigest>(self) -> Result<Blakeout<D>, Error> {
+        let hash_size = self.hash_size.unwrap_or(D::MAX_OUTPUT_SIZE);
+        validate_size::<D>(hash_size, self.hash_count)?;
+        if self.lookback_blocks == 0 {
+            return Err(Error::InvalidLookback(self.lookback_blocks));
+        }
+        if self.key.len() > D::MAX_KEY_SIZE {
+            return Err(Error::InvalidKeyLength(self.key.len()));
+        }
+        if self.salt.len() > D::MAX_SALT_SIZE {
+            return Err(Error::InvalidSaltLength(self.salt.len()));
+        }
+        if self.personal.len() > D::MAX_SALT_SIZE {
+            return Err(Error::InvalidPersonalLength(self.personal.len()));
+        }
+        let mut digest = Blakeout::build(hash_size, self.hash_count);
+        digest.lookback = self.lookback_blocks;
+        digest.mode = self.mode;
+        digest.max_input = self.max_input;
+        digest.key = self.key;
+        digest.salt = self.salt;
+        digest.personal = self.personal;
+        digest.domain = self.domain;
+        Ok(digest)
+    }
+}
+
+/// Checks scratchpad parameters for inner hash function `D` and returns the scratchpad size
+pub(crate) fn validate_size<D: InnerDigest>(hash_size: usize, hash_count: usize) -> Result<usize, Error> {
+    if hash_size == 0 || hash_size > D::MAX_OUTPUT_SIZE {
+        return Err(Error::InvalidHashSize(hash_size));
+    }
+    if hash_count < MIN_HASH_COUNT {
+        return Err(Error::InvalidHashCount(hash_count));
+    }
+    buffer_size(hash_size, hash_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Blakeout, BlakeoutBuilder, BlakeoutMode, Error};
+    const DATA: &[u8; 29] = b"Science is poetry of reality!";
+
+    #[test]
+    fn defaults() {
+        let mut digest = BlakeoutBuilder::new().build().unwrap();
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!("4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62", digest.result_str());
+    }
+
+    #[test]
+    fn personal_and_salt() {
+        let mut digest = BlakeoutBuilder::new().personal(b"personal").build().unwrap();
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!("79c7c3a5db36b36e27b8d8518f001e84c0d0d80208f5d4734a2e4f2e20e86a7f", digest.result_str());
+        let mut salted = BlakeoutBuilder::new().salt(b"salt").build().unwrap();
+        salted.update(DATA);
+        salted.compute();
+        assert_ne!(digest.result(), salted.result());
+        assert_ne!(&Blakeout::digest(DATA)[..], salted.result());
+    }
+
+    #[test]
+    fn lookback_blocks() {
+        let hash = |lookback_blocks: usize| {
+            let mut digest = BlakeoutBuilder::new().hash_count(1024).lookback_blocks(lookback_blocks).build().unwrap();
+            digest.update(DATA);
+            digest.compute();
+            digest.result_str()
+        };
+        assert_eq!("0c3172bd535d7e158a2d245037b8a9fb0357f6ec9e89701aa8cf00586408c574", hash(2));
+        assert_eq!("0f0e87004f0a21f51c021e3507a782b5a678840943c18ebfb8df7abe35d4940a", hash(4));
+        assert_eq!("31c87ad5b926a90ace9c7fa27ff1f287d7c92608ffaa3067f65e6a64999dc3f6", hash(1));
+        let mut digest = BlakeoutBuilder::new().lookback_blocks(2).build().unwrap();
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!(&Blakeout::digest(DATA)[..], digest.result());
+        // Windows longer than the scratchpad use everything hashed so far
+        assert_eq!(hash(1024), hash(usize::MAX));
+    }
+
+    #[test]
+    fn mode() {
+        let hash = |mode: BlakeoutMode| {
+            let mut digest = BlakeoutBuilder::new().hash_count(1024).mode(mode).build().unwrap();
+            digest.update(DATA);
+            digest.compute();
+            digest.result_str()
+        };
+        assert_eq!("0c3172bd535d7e158a2d245037b8a9fb0357f6ec9e89701aa8cf00586408c574", hash(BlakeoutMode::DualPass));
+        assert_eq!("66c8e9029843c8972f36f730e94c83bfdf9f0ce3a2cc087975cd360a367a0568", hash(BlakeoutMode::ForwardOnly));
+        let mut digest = BlakeoutBuilder::new().mode(BlakeoutMode::default()).build().unwrap();
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!(&Blakeout::digest(DATA)[..], digest.result());
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(Some(Error::InvalidHashCount(0)), BlakeoutBuilder::new().hash_count(0).build().err());
+        assert_eq!(Some(Error::InvalidLookback(0)), BlakeoutBuilder::new().lookback_blocks(0).build().err());
+        assert_eq!(Some(Error::InvalidSaltLength(9)), BlakeoutBuilder::new().salt(&[0; 9]).build().err());
+        assert_eq!(Some(Error::InvalidPersonalLength(9)), BlakeoutBuilder::new().personal(&[0; 9]).build().err());
+        assert_eq!(Some(Error::BufferTooLarge), BlakeoutBuilder::new().hash_count(usize::MAX).build().err());
+        assert_eq!(Some(Error::BufferTooLarge), BlakeoutBuilder::new().hash_count(usize::MAX / 32 + 1).build().err());
+        assert_eq!(Some(Error::BufferTooLarge), BlakeoutBuilder::new().hash_count(isize::MAX as usize / 16).build().err());
+    }
+}
@@ -0,0 +1,72 @@
+use std::time::{Duration, Instant};
+
+use crate::{Blakeout, DEFAULT_HASH_SIZE, MIN_HASH_COUNT};
+
+/// Largest hash count tried by calibration (512 MB scratchpad)
+const MAX_CALIBRATED_COUNT: usize = 1 << 24;
+/// Number of bisection steps after the target is bracketed
+const BISECT_STEPS: usize = 8;
+
+impl Blakeout {
+    /// Finds `hash_count` for `with_params` with 32 byte hashes, so that one hash
+    /// takes about `target` time on this machine.
+    ///
+    /// The count is doubled until hashing takes at least `target` and then bisected.
+    /// The result depends on the machine and its current load, so calibrate
+    /// on the machine where the hashes will be computed, and don't use it for
+    /// parameters that must be the same everywhere. It takes a few times `target` to run
+    /// and returns at most 2^24 (512 MB scratchpad).
+    pub fn calibrate(target: Duration) -> usize {
+        let mut low = MIN_HASH_COUNT;
+        let mut high = MIN_HASH_COUNT;
+        while measure(high) < target {
+            low = high;
+            if high >= MAX_CALIBRATED_COUNT {
+                return MAX_CALIBRATED_COUNT;
+            }
+            high *= 2;
+        }
+        for _ in 0..BISECT_STEPS {
+            if high - low <= 1 {
+                break;
+            }
+            let middle = low + (high - low) / 2;
+            if measure(middle) < target {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        high
+    }
+}
+
+/// Returns time of one hash with `hash_count` hashes in the scratchpad
+fn measure(hash_count: usize) -> Duration {
+    let mut digest = Blakeout::with_params(DEFAULT_HASH_SIZE, hash_count).expect("valid parameters");
+    let start = Instant::now();
+    digest.update(b"Blakeout calibration sample");
+    digest.compute();
+    start.elapsed()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+    use crate::Blakeout;
+
+    #[test]
+    fn calibrate() {
+        let target = Duration::from_millis(20);
+        let hash_count = Blakeout::calibrate(target);
+        assert!(hash_count >= 2);
+        let mut digest = Blakeout::with_params(32, hash_count).unwrap();
+        let start = Instant::now();
+        digest.update(b"other data");
+        digest.compute();
+        let elapsed = start.elapsed();
+        // Generous bounds, the machine can be busy with other tests
+        assert!(elapsed > target / 10, "{:?} for {} hashes", elapsed, hash_count);
+        assert!(elapsed < target * 10, "{:?} for {} hashes", elapsed, hash_count);
+    }
+}
@@ -0,0 +1,67 @@
+use core::fmt;
+
+/// Errors returned by Blakeout
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Hash size is not a legal Blake2s output length (1..=32)
+    InvalidHashSize(usize),
+    /// Hash count is too small to build a scratchpad (must be at least 2)
+    InvalidHashCount(usize),
+    /// Hash count is not a power of two where one is required
+    HashCountNotPowerOfTwo(usize),
+    /// Number of previous hashes used to get the next one is zero
+    InvalidLookback(usize),
+    /// Key is longer than 32 bytes
+    InvalidKeyLength(usize),
+    /// Salt is longer than 8 bytes
+    InvalidSaltLength(usize),
+    /// Personalization is longer than 8 bytes
+    InvalidPersonalLength(usize),
+    /// Inner Blake2s hasher rejected the output size
+    InvalidOutputSize,
+    /// Scratchpad size `hash_size * hash_count` doesn't fit in memory address space
+    BufferTooLarge,
+    /// Message would be longer than the limit set by `BlakeoutBuilder::max_input`
+    InputTooLarge,
+    /// Nothing was hashed yet, so there is no result
+    NotHashed,
+    /// Input has invalid length
+    InvalidLength(usize),
+    /// Input contains a character that is not a HEX digit
+    InvalidHex(char),
+    /// Saved state is malformed or saved by incompatible version
+    InvalidState,
+    /// Encoded parameters are malformed or encoded by incompatible version
+    InvalidParams,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidHashSize(size) => write!(f, "invalid hash size {}, must be in 1..=32 for Blake2s", size),
+            Error::InvalidHashCount(count) => write!(f, "invalid hash count {}, must be at least 2", count),
+            Error::HashCountNotPowerOfTwo(count) => write!(f, "invalid hash count {}, must be a power of two", count),
+            Error::InvalidLookback(blocks) => write!(f, "invalid lookback {}, must be at least 1 block", blocks),
+            Error::InvalidKeyLength(len) => write!(f, "invalid key length {}, must be at most 32 for Blake2s", len),
+            Error::InvalidSaltLength(len) => write!(f, "invalid salt length {}, must be at most 8 for Blake2s", len),
+            Error::InvalidPersonalLength(len) => write!(f, "invalid personalization length {}, must be at most 8 for Blake2s", len),
+            Error::InvalidOutputSize => write!(f, "invalid output size"),
+            Error::BufferTooLarge => write!(f, "scratchpad is too large"),
+            Error::InputTooLarge => write!(f, "message is longer than the limit"),
+            Error::NotHashed => write!(f, "nothing was hashed yet"),
+            Error::InvalidLength(len) => write!(f, "invalid input length {}", len),
+            Error::InvalidHex(c) => write!(f, "invalid HEX character {:?}", c),
+            Error::InvalidState => write!(f, "invalid saved state"),
+            Error::InvalidParams => write!(f, "invalid encoded parameters"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl From<digest::InvalidOutputSize> for Error {
+    fn from(_: digest::InvalidOutputSize) -> Self {
+        Error::InvalidOutputSize
+    }
+}
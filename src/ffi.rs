@@ -0,0 +1,147 @@
+//! C interface, enabled by `ffi` feature.
+//!
+//! All functions return 0 on success and -1 if a required pointer is null or hashing failed,
+//! panics never cross the boundary. Data pointer may be null only if its length is 0.
+//! Output buffers must have room for 32 bytes.
+//!
+//! To link it from C build the crate as a library for C, for example by
+//! `cargo rustc --release --features ffi --crate-type staticlib`.
+
+use std::os::raw::c_int;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+use std::slice;
+
+use crate::{Blakeout, DEFAULT_HASH_SIZE};
+
+const OK: c_int = 0;
+const ERROR: c_int = -1;
+
+/// Makes a slice from raw parts, empty one for null pointer with zero length
+unsafe fn input<'a>(data: *const u8, len: usize) -> Option<&'a [u8]> {
+    if data.is_null() {
+        if len == 0 { Some(&[]) } else { None }
+    } else {
+        Some(slice::from_raw_parts(data, len))
+    }
+}
+
+/// Runs `f` catching panics, returns -1 if it panicked
+fn guard<F: FnOnce() -> c_int>(f: F) -> c_int {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(ERROR)
+}
+
+/// Hashes `len` bytes at `data` with default parameters and writes 32 bytes of result to `out`.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, `out` must point to 32 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn blakeout_hash(data: *const u8, len: usize, out: *mut u8) -> c_int {
+    guard(|| {
+        let data = match input(data, len) {
+            Some(data) if !out.is_null() => data,
+            _ => return ERROR,
+        };
+        let hash = Blakeout::digest(data);
+        ptr::copy_nonoverlapping(hash.as_ptr(), out, DEFAULT_HASH_SIZE);
+        OK
+    })
+}
+
+/// Creates a hasher with default parameters, it must be freed by `blakeout_free`.
+/// Returns null if the hasher can't be created.
+#[no_mangle]
+pub extern "C" fn blakeout_new() -> *mut Blakeout {
+    catch_unwind(|| Box::into_raw(Box::new(Blakeout::new()))).unwrap_or(ptr::null_mut())
+}
+
+/// Appends `len` bytes at `data` to the message of the hasher, it is cheap:
+/// the whole message is hashed once by `blakeout_finalize`.
+///
+/// # Safety
+///
+/// `hasher` must be created by `blakeout_new` and not freed,
+/// `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn blakeout_update(hasher: *mut Blakeout, data: *const u8, len: usize) -> c_int {
+    guard(|| {
+        let data = match input(data, len) {
+            Some(data) if !hasher.is_null() => data,
+            _ => return ERROR,
+        };
+        match (*hasher).try_update(data) {
+            Ok(_) => OK,
+            Err(_) => ERROR,
+        }
+    })
+}
+
+/// Hashes the accumulated message, writes 32 bytes of result to `out` and resets the hasher
+/// for reuse, it still must be freed by `blakeout_free`.
+///
+/// # Safety
+///
+/// `hasher` must be created by `blakeout_new` and not freed,
+/// `out` must point to 32 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn blakeout_finalize(hasher: *mut Blakeout, out: *mut u8) -> c_int {
+    guard(|| {
+        if hasher.is_null() || out.is_null() {
+            return ERROR;
+        }
+        let hash = (*hasher).finalize_reset();
+        ptr::copy_nonoverlapping(hash.as_ptr(), out, DEFAULT_HASH_SIZE);
+        OK
+    })
+}
+
+/// Frees the hasher, null pointer is ignored.
+///
+/// # Safety
+///
+/// `hasher` must be created by `blakeout_new` and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn blakeout_free(hasher: *mut Blakeout) {
+    if !hasher.is_null() {
+        let _ = catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(hasher))));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+    use super::*;
+    const DATA: &[u8; 29] = b"Science is poetry of reality!";
+
+    #[test]
+    fn one_shot() {
+        let mut out = [0u8; 32];
+        assert_eq!(OK, unsafe { blakeout_hash(DATA.as_ptr(), DATA.len(), out.as_mut_ptr()) });
+        assert_eq!(Blakeout::digest(DATA), out);
+        assert_eq!(OK, unsafe { blakeout_hash(ptr::null(), 0, out.as_mut_ptr()) });
+        assert_eq!(Blakeout::digest(b""), out);
+        assert_eq!(ERROR, unsafe { blakeout_hash(ptr::null(), 1, out.as_mut_ptr()) });
+        assert_eq!(ERROR, unsafe { blakeout_hash(DATA.as_ptr(), DATA.len(), ptr::null_mut()) });
+    }
+
+    #[test]
+    fn incremental() {
+        let mut out = [0u8; 32];
+        unsafe {
+            let hasher = blakeout_new();
+            assert!(!hasher.is_null());
+            assert_eq!(OK, blakeout_update(hasher, DATA.as_ptr(), 10));
+            assert_eq!(OK, blakeout_update(hasher, DATA[10..].as_ptr(), DATA.len() - 10));
+            assert_eq!(OK, blakeout_finalize(hasher, out.as_mut_ptr()));
+            assert_eq!(Blakeout::digest(DATA), out);
+            assert_eq!(ERROR, blakeout_update(hasher, ptr::null(), 1));
+            assert_eq!(ERROR, blakeout_finalize(hasher, ptr::null_mut()));
+            blakeout_free(hasher);
+
+            assert_eq!(ERROR, blakeout_update(ptr::null_mut(), DATA.as_ptr(), DATA.len()));
+            assert_eq!(ERROR, blakeout_finalize(ptr::null_mut(), out.as_mut_ptr()));
+            blakeout_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::Blakeout;
+
+/// Writes integers in little-endian byte order, the default methods use native one,
+/// which would make hashes depend on the target
+macro_rules! write_le {
+    ($($name:ident($int:ty)),* $(,)?) => {
+        $(
+            fn $name(&mut self, i: $int) {
+                self.write(&i.to_le_bytes());
+            }
+        )*
+    };
+}
+
+/// Blakeout based `Hasher`, can be used to back a `HashMap` or `HashSet`.
+///
+/// **Note:** every `finish` runs the whole 2 MB memory hard routine over the written bytes,
+/// so each lookup takes milliseconds instead of nanoseconds. Use it only
+/// when resistance to collision attacks matters much more than speed.
+#[derive(Clone, Default)]
+pub struct BlakeoutHasher {
+    digest: Blakeout,
+}
+
+impl BlakeoutHasher {
+    /// Creates new hasher with default Blakeout parameters
+    pub fn new() -> Self {
+        BlakeoutHasher { digest: Blakeout::new() }
+    }
+}
+
+impl Hasher for BlakeoutHasher {
+    /// Returns first 8 bytes of the result as little-endian `u64`, the written bytes
+    /// are hashed by a copy of the hasher, so more bytes can be written after it
+    fn finish(&self) -> u64 {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&self.digest.clone().finalize()[..8]);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.digest.update(bytes);
+    }
+
+    write_le! {
+        write_u8(u8), write_u16(u16), write_u32(u32), write_u64(u64), write_u128(u128),
+        write_i8(i8), write_i16(i16), write_i32(i32), write_i64(i64), write_i128(i128),
+    }
+
+    /// Writes `usize` as `u64`, so the hash is the same on 32 and 64 bit targets
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    /// Writes `isize` as `i64`, so the hash is the same on 32 and 64 bit targets
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64);
+    }
+}
+
+/// Builds `BlakeoutHasher`s for `HashMap` and `HashSet`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BuildBlakeoutHasher;
+
+impl BuildHasher for BuildBlakeoutHasher {
+    type Hasher = BlakeoutHasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        BlakeoutHasher::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::hash::{BuildHasher, Hasher};
+    use std::collections::HashMap;
+    use crate::{Blakeout, BlakeoutHasher, BuildBlakeoutHasher};
+
+    #[test]
+    fn finish_is_result_prefix() {
+        let mut hasher = BlakeoutHasher::new();
+        hasher.write(b"Science is poetry of reality!");
+        assert_eq!(0x32545dffda92e84b, hasher.finish());
+        assert_eq!(BuildBlakeoutHasher.build_hasher().finish().to_le_bytes(), Blakeout::digest([])[..8]);
+    }
+
+    #[test]
+    fn integers_are_little_endian() {
+        let mut hasher = BlakeoutHasher::new();
+        hasher.write_u32(0x0403_0201);
+        let mut bytes = BlakeoutHasher::new();
+        bytes.write(&[1, 2, 3, 4]);
+        assert_eq!(bytes.finish(), hasher.finish());
+
+        let mut hasher = BlakeoutHasher::new();
+        hasher.write_usize(1);
+        let mut wide = BlakeoutHasher::new();
+        wide.write_u64(1);
+        assert_eq!(wide.finish(), hasher.finish());
+    }
+
+    #[test]
+    fn hash_map() {
+        let mut map = HashMap::with_hasher(BuildBlakeoutHasher);
+        map.insert("one", 1);
+        map.insert("two", 2);
+        assert_eq!(Some(&1), map.get("one"));
+        assert_eq!(Some(&2), map.get("two"));
+        assert_eq!(None, map.get("three"));
+    }
+}
@@ -0,0 +1,107 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::Error;
+
+const LOWER: &[u8; 16] = b"0123456789abcdef";
+const UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Case of HEX digits `a-f`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum HexCase {
+    /// `0-9a-f`
+    #[default]
+    Lower,
+    /// `0-9A-F`
+    Upper,
+}
+
+/// Convert bytes array to HEX format
+pub fn to_hex(buf: &[u8]) -> String {
+    to_hex_with(buf, false, None)
+}
+
+/// Convert bytes array to HEX format in chosen case, optionally separating bytes with `sep`
+pub fn to_hex_with(buf: &[u8], upper: bool, sep: Option<char>) -> String {
+    let table = if upper { UPPER } else { LOWER };
+    let sep_len = sep.map_or(0, char::len_utf8) * buf.len().saturating_sub(1);
+    let mut result = String::with_capacity(buf.len() * 2 + sep_len);
+    for (i, x) in buf.iter().enumerate() {
+        if let (Some(sep), true) = (sep, i > 0) {
+            result.push(sep);
+        }
+        result.push(table[(x >> 4) as usize] as char);
+        result.push(table[(x & 0x0f) as usize] as char);
+    }
+    result
+}
+
+/// Writes bytes in lowercase HEX format to `w` without allocating
+pub(crate) fn write_hex<W: fmt::Write>(w: &mut W, buf: &[u8]) -> fmt::Result {
+    for x in buf {
+        w.write_char(LOWER[(x >> 4) as usize] as char)?;
+        w.write_char(LOWER[(x & 0x0f) as usize] as char)?;
+    }
+    Ok(())
+}
+
+/// Parses HEX string in lower or upper case back to bytes
+pub fn from_hex(s: &str) -> Result<Vec<u8>, Error> {
+    let mut result = Vec::with_capacity(s.len() / 2);
+    let mut high = None;
+    for c in s.chars() {
+        let nibble = c.to_digit(16).ok_or(Error::InvalidHex(c))? as u8;
+        match high.take() {
+            None => high = Some(nibble),
+            Some(high) => result.push(high << 4 | nibble),
+        }
+    }
+    if high.is_some() {
+        return Err(Error::InvalidLength(s.len()));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, from_hex, to_hex, to_hex_with};
+    use crate::xorshift::XorShift;
+
+    #[test]
+    fn round_trip() {
+        let bytes: alloc::vec::Vec<u8> = (0..=255).collect();
+        assert_eq!(bytes, from_hex(&to_hex(&bytes)).unwrap());
+        assert_eq!(alloc::vec![0xab, 0xcd, 0xef], from_hex("ABcdEf").unwrap());
+        assert!(from_hex("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(Err(Error::InvalidLength(3)), from_hex("abc"));
+        assert_eq!(Err(Error::InvalidHex('g')), from_hex("0g"));
+        assert_eq!(Err(Error::InvalidHex('я')), from_hex("яя"));
+    }
+
+    #[test]
+    fn matches_format() {
+        let mut rng = XorShift(0x2545f4914f6cdd1d);
+        for len in 0..100 {
+            let mut bytes = alloc::vec![0u8; len];
+            rng.fill(&mut bytes);
+            let expected: alloc::string::String = bytes.iter().map(|x| alloc::format!("{:01$x}", x, 2)).collect();
+            assert_eq!(expected, to_hex(&bytes));
+            assert_eq!(expected.to_uppercase(), to_hex_with(&bytes, true, None));
+        }
+    }
+
+    #[test]
+    fn formatting() {
+        let bytes = [0x4b, 0xe8, 0x92, 0x0a];
+        assert_eq!("4be8920a", to_hex(&bytes));
+        assert_eq!("4BE8920A", to_hex_with(&bytes, true, None));
+        assert_eq!("4B:E8:92:0A", to_hex_with(&bytes, true, Some(':')));
+        assert_eq!("4b e8 92 0a", to_hex_with(&bytes, false, Some(' ')));
+        assert_eq!("", to_hex_with(&[], true, Some(':')));
+    }
+}
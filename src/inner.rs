@@ -0,0 +1,82 @@
+use digest09::{Update, VariableOutput};
+use blake2::{VarBlake2b, VarBlake2s};
+
+/// Hash function used to fill the scratchpad and to hash it.
+///
+/// Implemented for Blake2s (`VarBlake2s`), the original and default one,
+/// Blake2b (`VarBlake2b`) giving up to 64 byte results, and with `blake3` feature
+/// for BLAKE3 (`blake3::Hasher`).
+pub trait InnerDigest: Clone {
+    /// Identifier of the hash function written to saved states, must be unique
+    /// among implementations, so a state can't be restored with another function
+    const ID: u8;
+    /// Maximum output size in bytes, it is also the default result size, must be at most 64
+    const MAX_OUTPUT_SIZE: usize;
+    /// Maximum key size in bytes
+    const MAX_KEY_SIZE: usize;
+    /// Maximum salt and personalization size in bytes
+    const MAX_SALT_SIZE: usize;
+
+    /// Creates new hasher, the sizes of parameters are validated by the caller
+    fn new(output_size: usize, key: &[u8], salt: &[u8], personal: &[u8]) -> Self;
+
+    /// Hashes supplied data
+    fn update(&mut self, data: &[u8]);
+
+    /// Writes the hash to `out` and resets the hasher for reuse
+    fn finalize_to(&mut self, out: &mut [u8]);
+}
+
+macro_rules! impl_inner_digest {
+    ($state:ty, $id:expr, $output:expr, $salt:expr) => {
+        impl InnerDigest for $state {
+            const ID: u8 = $id;
+            const MAX_OUTPUT_SIZE: usize = $output;
+            const MAX_KEY_SIZE: usize = $output;
+            const MAX_SALT_SIZE: usize = $salt;
+
+            fn new(output_size: usize, key: &[u8], salt: &[u8], personal: &[u8]) -> Self {
+                <$state>::with_params(key, salt, personal, output_size)
+            }
+
+            fn update(&mut self, data: &[u8]) {
+                Update::update(self, data);
+            }
+
+            fn finalize_to(&mut self, out: &mut [u8]) {
+                self.finalize_variable_reset(|buf| out.copy_from_slice(buf));
+            }
+        }
+    };
+}
+
+impl_inner_digest!(VarBlake2s, 1, 32, 8);
+impl_inner_digest!(VarBlake2b, 2, 64, 16);
+
+/// BLAKE3 gives up to 32 byte results from its extendable output. It has no salt
+/// and personalization, and its key must be 32 bytes, so a key of any length up to 32 bytes
+/// is hashed by BLAKE3 to get one, empty key gives unkeyed hasher.
+#[cfg(feature = "blake3")]
+impl InnerDigest for blake3::Hasher {
+    const ID: u8 = 3;
+    const MAX_OUTPUT_SIZE: usize = 32;
+    const MAX_KEY_SIZE: usize = 32;
+    const MAX_SALT_SIZE: usize = 0;
+
+    fn new(_output_size: usize, key: &[u8], _salt: &[u8], _personal: &[u8]) -> Self {
+        if key.is_empty() {
+            blake3::Hasher::new()
+        } else {
+            blake3::Hasher::new_keyed(blake3::hash(key).as_bytes())
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        blake3::Hasher::update(self, data);
+    }
+
+    fn finalize_to(&mut self, out: &mut [u8]) {
+        self.finalize_xof().fill(out);
+        self.reset();
+    }
+}
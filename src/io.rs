@@ -0,0 +1,523 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::vec::Vec;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{Blakeout, DEFAULT_HASH_SIZE, Error, InnerDigest, to_hex};
+
+/// Default size of the buffer used to read data from readers
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+impl<D: InnerDigest> Blakeout<D> {
+    /// Reads `reader` to the end and appends all read bytes to the message.
+    ///
+    /// Returns the number of bytes read. If reading fails the bytes read before
+    /// the error stay in the message. Reading beyond
+    /// `BlakeoutBuilder::max_input` fails with `Error::InputTooLarge` inside `io::Error`.
+    pub fn update_from_reader<R: Read>(&mut self, reader: &mut R) -> io::Result<u64> {
+        self.update_from_reader_with_progress(reader, |_| {})
+    }
+
+    /// Same as `update_from_reader`, but reads by blocks of `buf_size` bytes instead of 64 KB,
+    /// zero size is the same as one byte
+    pub fn update_from_reader_buffered<R: Read>(&mut self, reader: &mut R, buf_size: usize) -> io::Result<u64> {
+        self.read_blocks(reader, buf_size, |_| Ok(()))
+    }
+
+    /// Same as `update_from_reader`, but reserves memory for `expected_len` more bytes
+    /// of the message upfront, for example from `Content-Length` or file metadata.
+    ///
+    /// It is only a hint: the reader is still read to the end, so the result is the same
+    /// if it delivers more or fewer bytes. The reservation is limited by
+    /// `BlakeoutBuilder::max_input` and skipped if that much memory can't be reserved.
+    pub fn update_from_reader_sized<R: Read>(&mut self, reader: &mut R, expected_len: usize) -> io::Result<u64> {
+        let available = self.max_input.saturating_sub(self.input.len());
+        // A failed reservation is not an error, the message grows as it is read
+        let _ = self.input.try_reserve(expected_len.min(available));
+        self.update_from_reader(reader)
+    }
+
+    /// Same as `update_from_reader`, but calls `on_progress` with the number of bytes
+    /// read so far after every read block, for example to show a progress bar
+    pub fn update_from_reader_with_progress<R, F>(&mut self, reader: &mut R, mut on_progress: F) -> io::Result<u64>
+    where
+        R: Read,
+        F: FnMut(u64),
+    {
+        self.read_blocks(reader, READ_BUFFER_SIZE, |total| {
+            on_progress(total);
+            Ok(())
+        })
+    }
+
+    /// Same as `update_from_reader`, but checks `should_cancel` between read blocks and stops
+    /// if it is set, returning an error with `Cancelled` inside, see `Cancelled::is_cancelled`.
+    ///
+    /// The bytes read before cancelling stay in the message, call `reset` to start over.
+    pub fn update_from_reader_cancellable<R: Read>(&mut self, reader: &mut R, should_cancel: &AtomicBool) -> io::Result<u64> {
+        let check = || {
+            if should_cancel.load(Ordering::Relaxed) {
+                return Err(io::Error::other(Cancelled));
+            }
+            Ok(())
+        };
+        check()?;
+        self.read_blocks(reader, READ_BUFFER_SIZE, |_| check())
+    }
+
+    /// Reads `reader` by blocks of up to `buf_size` bytes appending them to the message
+    /// and calling `on_block` with the number of bytes read so far.
+    /// Short reads don't stop reading, only the end of `reader` does, or the first error
+    /// of reading or of `on_block`.
+    fn read_blocks<R, F>(&mut self, reader: &mut R, buf_size: usize, mut on_block: F) -> io::Result<u64>
+    where
+        R: Read,
+        F: FnMut(u64) -> io::Result<()>,
+    {
+        let mut buffer = vec![0u8; buf_size.max(1)];
+        let mut total = 0u64;
+        loop {
+            let n = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            self.check_input(n).map_err(io::Error::other)?;
+            self.append(&buffer[..n]);
+            total += n as u64;
+            on_block(total)?;
+        }
+        Ok(total)
+    }
+}
+
+/// Error put inside `io::Error` when hashing is cancelled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Cancelled {
+    /// Checks if `error` is caused by cancelling
+    pub fn is_cancelled(error: &io::Error) -> bool {
+        error.get_ref().is_some_and(|e| e.is::<Cancelled>())
+    }
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("hashing cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Hashes contents of the file at `path` with default parameters and returns the result
+pub fn hash_file<P: AsRef<Path>>(path: P) -> io::Result<[u8; DEFAULT_HASH_SIZE]> {
+    hash_file_with(&mut Blakeout::new(), path.as_ref())
+}
+
+/// Same as `hash_file`, but maps the file to memory and hashes the whole mapping at once
+/// instead of reading it by blocks.
+///
+/// The file must not be changed by other processes while it is hashed, otherwise the result
+/// is unpredictable. Empty files are hashed without mapping, as zero length can't be mapped.
+#[cfg(feature = "mmap")]
+pub fn hash_file_mmap<P: AsRef<Path>>(path: P) -> io::Result<[u8; DEFAULT_HASH_SIZE]> {
+    let file = File::open(path)?;
+    if file.metadata()?.len() == 0 {
+        return Ok(Blakeout::digest([]));
+    }
+    // Safe as long as the file is not modified while mapped, see above
+    let mapping = unsafe { memmap2::Mmap::map(&file)? };
+    Ok(Blakeout::digest(&mapping[..]))
+}
+
+/// Hashes every file in `paths` by `hash_file` and returns the paths with their results
+/// in the same order, a failure to read one file doesn't stop hashing of the others.
+///
+/// With `rayon` feature enabled the files are distributed across rayon's thread pool,
+/// every thread uses its own scratchpad.
+pub fn hash_files(paths: &[PathBuf]) -> Vec<(PathBuf, io::Result<[u8; DEFAULT_HASH_SIZE]>)> {
+    let hash = |hasher: &mut Blakeout, path: &PathBuf| (path.clone(), hash_file_with(hasher, path));
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        paths.par_iter().map_init(Blakeout::new, hash).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        let mut hasher = Blakeout::new();
+        paths.iter().map(|path| hash(&mut hasher, path)).collect()
+    }
+}
+
+/// Hashes contents of the file at `path` by `hasher`, which is left reset
+fn hash_file_with(hasher: &mut Blakeout, path: &Path) -> io::Result<[u8; DEFAULT_HASH_SIZE]> {
+    let mut file = File::open(path)?;
+    let result = hasher.update_from_reader(&mut file);
+    if let Err(e) = result {
+        hasher.reset();
+        return Err(e);
+    }
+    Ok(hasher.finalize_reset())
+}
+
+/// Same as `hash_file`, but returns the result as HEX string
+pub fn hash_file_hex<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    hash_file(path).map(|hash| to_hex(&hash))
+}
+
+/// Hashes contents of the file at `path` with default parameters and compares the result
+/// with `expected` in constant time, returns `false` on mismatch
+pub fn verify_file<P: AsRef<Path>>(path: P, expected: &[u8; DEFAULT_HASH_SIZE]) -> io::Result<bool> {
+    let mut file = File::open(path)?;
+    let mut hasher = Blakeout::new();
+    hasher.update_from_reader(&mut file)?;
+    hasher.compute();
+    Ok(hasher.verify(expected))
+}
+
+/// Reader adapter that hashes all bytes read through it.
+///
+/// The bytes are accumulated and hashed once by `digest`.
+pub struct HashingReader<R: Read> {
+    inner: R,
+    hasher: Blakeout,
+}
+
+impl<R: Read> HashingReader<R> {
+    /// Wraps `inner` reader, hashing with default parameters
+    pub fn new(inner: R) -> Self {
+        Self::with_hasher(inner, Blakeout::new())
+    }
+
+    /// Wraps `inner` reader, hashing with supplied hasher
+    pub fn with_hasher(inner: R, hasher: Blakeout) -> Self {
+        HashingReader { inner, hasher }
+    }
+
+    /// Consumes the adapter and returns the hash of all bytes read
+    pub fn digest(self) -> [u8; DEFAULT_HASH_SIZE] {
+        self.hasher.finalize()
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    /// Reads at most as many bytes as fit in `BlakeoutBuilder::max_input`, when the message
+    /// is full reading fails without reading from the inner reader, so no bytes are lost.
+    /// It fails even if the inner reader is at its end, as it can't be known without reading.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.hasher.max_input.saturating_sub(self.hasher.input.len());
+        if available == 0 && !buf.is_empty() {
+            return Err(io::Error::other(Error::InputTooLarge));
+        }
+        let len = buf.len().min(available);
+        let n = self.inner.read(&mut buf[..len])?;
+        self.hasher.append(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Writer adapter that hashes all bytes written through it.
+///
+/// The bytes are accumulated and hashed once by `digest`.
+pub struct HashingWriter<W: Write> {
+    inner: W,
+    hasher: Blakeout,
+}
+
+impl<W: Write> HashingWriter<W> {
+    /// Wraps `inner` writer, hashing with default parameters
+    pub fn new(inner: W) -> Self {
+        Self::with_hasher(inner, Blakeout::new())
+    }
+
+    /// Wraps `inner` writer, hashing with supplied hasher
+    pub fn with_hasher(inner: W, hasher: Blakeout) -> Self {
+        HashingWriter { inner, hasher }
+    }
+
+    /// Consumes the adapter and returns the hash of all bytes written
+    pub fn digest(self) -> [u8; DEFAULT_HASH_SIZE] {
+        self.hasher.finalize()
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    /// Hashes only the bytes accepted by the inner writer, fails without writing
+    /// if they would make the message longer than `BlakeoutBuilder::max_input`
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.hasher.check_input(buf.len()).map_err(io::Error::other)?;
+        let n = self.inner.write(buf)?;
+        self.hasher.append(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::{self, Cursor, Read, Write};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use crate::{Blakeout, BlakeoutBuilder, Cancelled, Error, HashingReader, HashingWriter, hash_file, hash_file_hex};
+
+    const DATA: &[u8; 29] = b"Science is poetry of reality!";
+
+    #[test]
+    fn update_from_reader() {
+        let data = DATA.repeat(1000);
+        let mut digest = Blakeout::new();
+        assert_eq!(data.len() as u64, digest.update_from_reader(&mut Cursor::new(&data)).unwrap());
+        let mut expected = Blakeout::new();
+        expected.update(&data);
+        assert_eq!(expected.compute(), digest.compute());
+
+        assert_eq!(0, digest.update_from_reader(&mut Cursor::new(&[])).unwrap());
+        assert_eq!(expected.result(), digest.compute());
+    }
+
+    #[test]
+    fn update_from_reader_sized() {
+        let data = DATA.repeat(1000);
+        for &expected_len in &[data.len(), 10, 0, data.len() * 2] {
+            let mut digest = Blakeout::new();
+            let mut reader = ShortReads(Cursor::new(&data));
+            assert_eq!(data.len() as u64, digest.update_from_reader_sized(&mut reader, expected_len).unwrap());
+            assert!(digest.input.capacity() >= expected_len);
+            assert_eq!(Blakeout::digest(&data), digest.finalize(), "expected length {}", expected_len);
+        }
+        let mut digest = BlakeoutBuilder::new().max_input(100).build().unwrap();
+        digest.update_from_reader_sized(&mut Cursor::new(&DATA), usize::MAX).unwrap();
+        digest.compute();
+        assert!(digest.input.capacity() < 1000);
+        assert_eq!(&Blakeout::digest(DATA)[..], digest.result());
+
+        // A bogus hint on an unlimited hasher is ignored
+        let mut digest = Blakeout::new();
+        digest.update_from_reader_sized(&mut Cursor::new(&DATA), usize::MAX).unwrap();
+        assert_eq!(Blakeout::digest(DATA), digest.finalize());
+    }
+
+    #[test]
+    fn update_from_reader_buffered() {
+        let data = DATA.repeat(1000);
+        for &size in &[0, 1, 7, 1000, 100_000] {
+            let mut digest = Blakeout::new();
+            let mut reader = ShortReads(Cursor::new(&data));
+            assert_eq!(data.len() as u64, digest.update_from_reader_buffered(&mut reader, size).unwrap());
+            assert_eq!(Blakeout::digest(&data), digest.finalize(), "buffer size {}", size);
+        }
+        let mut digest = Blakeout::new();
+        digest.update_from_reader(&mut ShortReads(Cursor::new(&data))).unwrap();
+        digest.compute();
+        assert_eq!(Blakeout::digest(&data), digest.finalize());
+    }
+
+    /// Returns at most 100 bytes on every read, as pipes and sockets may do
+    struct ShortReads<R>(R);
+
+    impl<R: Read> Read for ShortReads<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(100);
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    #[test]
+    fn short_chunks_and_interrupts() {
+        let chunks = [&DATA[..5], &DATA[5..6], &DATA[6..20], &DATA[20..]];
+        let mut reader = Chunks { chunks: chunks.to_vec(), interrupt: true };
+        let mut digest = Blakeout::new();
+        assert_eq!(DATA.len() as u64, digest.update_from_reader(&mut reader).unwrap());
+        assert_eq!(Blakeout::digest(DATA), digest.finalize());
+    }
+
+    /// Returns the chunks one by one, every chunk is preceded by `ErrorKind::Interrupted`
+    struct Chunks<'a> {
+        chunks: Vec<&'a [u8]>,
+        interrupt: bool,
+    }
+
+    impl Read for Chunks<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.chunks.is_empty() {
+                return Ok(0);
+            }
+            self.interrupt = !self.interrupt;
+            if !self.interrupt {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            let chunk = self.chunks.remove(0);
+            buf[..chunk.len()].copy_from_slice(chunk);
+            Ok(chunk.len())
+        }
+    }
+
+    #[test]
+    fn progress() {
+        let data = DATA.repeat(5000);
+        let mut reported = Vec::new();
+        let mut digest = Blakeout::new();
+        let total = digest.update_from_reader_with_progress(&mut Cursor::new(&data), |n| reported.push(n)).unwrap();
+        assert_eq!(data.len() as u64, total);
+        assert_eq!(Some(&total), reported.last());
+        assert!(reported.len() > 1);
+        let steps: u64 = reported.iter().zip([0].iter().chain(reported.iter())).map(|(n, prev)| n - prev).sum();
+        assert_eq!(total, steps);
+        assert_eq!(Blakeout::digest(&data), digest.finalize());
+    }
+
+    #[test]
+    fn cancel() {
+        let data = DATA.repeat(1000);
+        let flag = AtomicBool::new(false);
+        let mut reader = Cursor::new(&data).chain(Flagging(&flag)).chain(Cursor::new(&data));
+        let mut digest = Blakeout::new();
+        let error = digest.update_from_reader_cancellable(&mut reader, &flag).unwrap_err();
+        assert!(Cancelled::is_cancelled(&error));
+        assert!(!Cancelled::is_cancelled(&io::Error::other("other")));
+        assert!(!digest.is_dirty());
+
+        digest.reset();
+        flag.store(false, Ordering::Relaxed);
+        digest.update_from_reader_cancellable(&mut Cursor::new(&data), &flag).unwrap();
+        digest.compute();
+        assert_eq!(Blakeout::digest(&data), digest.finalize());
+    }
+
+    /// Sets the flag when read, as if cancelling came while reading
+    struct Flagging<'a>(&'a AtomicBool);
+
+    impl Read for Flagging<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0.store(true, Ordering::Relaxed);
+            buf[0] = 0;
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn file() {
+        let path = std::env::temp_dir().join(format!("blakeout-file-{}", std::process::id()));
+        fs::write(&path, DATA).unwrap();
+        let hash = hash_file(&path).unwrap();
+        let hex = hash_file_hex(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(Blakeout::digest(DATA), hash);
+        assert_eq!("4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62", hex);
+        assert!(hash_file(&path).is_err());
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn file_mmap() {
+        let path = std::env::temp_dir().join(format!("blakeout-mmap-{}", std::process::id()));
+        let data: Vec<u8> = (0..100_000u32).map(|x| (x % 251) as u8).collect();
+        fs::write(&path, &data).unwrap();
+        let mapped = super::hash_file_mmap(&path).unwrap();
+        let streamed = hash_file(&path).unwrap();
+        fs::write(&path, b"").unwrap();
+        let empty = super::hash_file_mmap(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(streamed, mapped);
+        assert_eq!(Blakeout::digest(&data), mapped);
+        assert_eq!(Blakeout::digest([]), empty);
+        assert!(super::hash_file_mmap(&path).is_err());
+    }
+
+    #[test]
+    fn hash_files() {
+        let dir = std::env::temp_dir();
+        let first = dir.join(format!("blakeout-files-1-{}", std::process::id()));
+        let second = dir.join(format!("blakeout-files-2-{}", std::process::id()));
+        let missing = dir.join(format!("blakeout-files-missing-{}", std::process::id()));
+        fs::write(&first, DATA).unwrap();
+        fs::write(&second, b"").unwrap();
+        let paths = [first.clone(), missing.clone(), second.clone()];
+        let results = super::hash_files(&paths);
+        fs::remove_file(&first).unwrap();
+        fs::remove_file(&second).unwrap();
+
+        assert_eq!(3, results.len());
+        assert_eq!(first, results[0].0);
+        assert_eq!(Blakeout::digest(DATA), *results[0].1.as_ref().unwrap());
+        assert_eq!(missing, results[1].0);
+        assert!(results[1].1.is_err());
+        assert_eq!(second, results[2].0);
+        assert_eq!(Blakeout::digest([]), *results[2].1.as_ref().unwrap());
+    }
+
+    #[test]
+    fn max_input() {
+        let data = DATA.repeat(100);
+        let limited = || BlakeoutBuilder::new().hash_count(1024).max_input(1000).build().unwrap();
+        let error = limited().update_from_reader(&mut Cursor::new(&data)).unwrap_err();
+        assert_eq!(Some(&Error::InputTooLarge), error.get_ref().and_then(|e| e.downcast_ref::<Error>()));
+
+        let mut writer = HashingWriter::with_hasher(Vec::new(), limited());
+        writer.write_all(&data[..1000]).unwrap();
+        assert!(writer.write_all(&[0]).is_err());
+        assert_eq!(1000, writer.inner.len());
+
+        // The bytes beyond the limit are left in the inner reader
+        let mut cursor = Cursor::new(&data);
+        let mut reader = HashingReader::with_hasher(&mut cursor, limited());
+        let mut read = Vec::new();
+        let error = reader.read_to_end(&mut read).unwrap_err();
+        assert_eq!(Some(&Error::InputTooLarge), error.get_ref().and_then(|e| e.downcast_ref::<Error>()));
+        assert_eq!(&data[..1000], &read[..]);
+        let digest = reader.digest();
+        assert_eq!(1000, cursor.position());
+        let mut expected = limited();
+        expected.update(&data[..1000]);
+        assert_eq!(expected.finalize(), digest);
+    }
+
+    #[test]
+    fn verify_file() {
+        let path = std::env::temp_dir().join(format!("blakeout-verify-{}", std::process::id()));
+        let expected = Blakeout::digest(DATA);
+        fs::write(&path, DATA).unwrap();
+        let valid = super::verify_file(&path, &expected).unwrap();
+        let mut corrupted = DATA.to_vec();
+        corrupted[0] ^= 1;
+        fs::write(&path, &corrupted).unwrap();
+        let invalid = super::verify_file(&path, &expected).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(valid);
+        assert!(!invalid);
+        assert!(super::verify_file(&path, &expected).is_err());
+    }
+
+    #[test]
+    fn reader() {
+        let data = DATA.repeat(1000);
+        let path = std::env::temp_dir().join(format!("blakeout-reader-{}", std::process::id()));
+        fs::write(&path, &data).unwrap();
+        let mut reader = HashingReader::new(fs::File::open(&path).unwrap());
+        let mut read = Vec::new();
+        reader.read_to_end(&mut read).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(data, read);
+        assert_eq!(Blakeout::digest(&data), reader.digest());
+    }
+
+    #[test]
+    fn writer() {
+        let mut sink = Vec::new();
+        let mut writer = HashingWriter::new(&mut sink);
+        for _ in 0..1000 {
+            writer.write_all(DATA).unwrap();
+        }
+        writer.flush().unwrap();
+        let digest = writer.digest();
+        assert_eq!(DATA.repeat(1000), sink);
+        assert_eq!(Blakeout::digest(&sink), digest);
+    }
+}
@@ -5,7 +5,6 @@
 //! `Blakeout` can be used in the following way:
 //!
 //! ```rust
-//! use crypto::digest::Digest;
 //! use blakeout::Blakeout;
 //!
 //! // create a Blakeout object, it will hash your bytes for you
@@ -14,21 +13,219 @@
 //! // write input message
 //! hasher.update(b"hello world");
 //!
-//! // read hash digest and consume hasher
+//! // hash the message once and read hash digest
+//! hasher.compute();
 //! let res = hasher.result_str();
 //! assert_eq!(res, "6cc4bddb52416711be65e4b0201106fda4ceb0de48dfdce7e3a136e490d8586f");
 //! ```
+//!
+//! It also implements the traits from the `digest` crate, so it can be used by any
+//! code generic over `digest::Digest`:
+//!
+//! ```rust
+//! use blakeout::Blakeout;
+//! use digest::Digest;
+//!
+//! fn hash<D: Digest>(data: &[u8]) -> Vec<u8> {
+//!     let mut hasher = D::new();
+//!     hasher.update(data);
+//!     hasher.finalize().to_vec()
+//! }
+//!
+//! assert_eq!(hash::<Blakeout>(b"hello world").len(), 32);
+//! ```
+//!
+//! # Portability
+//!
+//! Results don't depend on the target: the algorithm works on bytes only, and every
+//! integer that is hashed or converted from the result (XOF counter, domain length,
+//! saved state, `BlakeoutHasher` integers and `finish`) uses little-endian byte order.
+//!
+//! # Features
+//!
+//! - `std` (default) - implementations depending on the standard library,
+//!   without it the crate is `no_std` and needs only `alloc`.
+//! - `base64` - `result_base64` encoding.
+//! - `blake3` - `Blakeout3` using BLAKE3 as inner hash, not compatible with the original.
+//! - `bs58` - `result_base58` encoding.
+//! - `bytes` - hashing of `bytes::Buf` by `update_buf`.
+//! - `ffi` - C interface in `ffi` module.
+//! - `mmap` - `hash_file_mmap` hashing memory mapped files.
+//! - `prefetch` - prefetch the scratchpad while filling it, on x86_64 only. The access
+//!   is sequential, so hardware prefetchers usually do it as well, compare with the benchmark.
+//! - `rayon` - parallel `hash_many`.
+//! - `serde` - serialization of `Output`.
+//! - `simd` - reverse the scratchpad for the backward pass by AVX2 on x86_64 or NEON on aarch64,
+//!   the results are the same as without it. Hashing dominates the time, so the gain is small.
+//! - `subtle` - use `subtle` crate for constant time comparison in `verify`.
+//! - `testutil` - statistical diagnostics in `testutil` module.
+//! - `tokio` - `hash_async` and `hash_file_async` hashing on Tokio blocking thread pool.
+//! - `wasm` - WebAssembly bindings in `wasm` module.
+//! - `zeroize` - wipe the scratchpad, message and result on drop, also of `BlakeoutStatic`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+#[cfg(all(test, not(feature = "std")))]
+extern crate std;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use core::marker::PhantomData;
+
+use digest::{FixedOutput, FixedOutputReset, HashMarker, OutputSizeUser, Reset, Update};
+use digest::consts::{U32, U64};
+use digest::core_api::BlockSizeUser;
 
-use digest::Digest;
-use blake2::Blake2s;
+pub use blake2::{VarBlake2b, VarBlake2s};
+
+#[cfg(feature = "bytes")]
+mod buf;
+mod builder;
+#[cfg(feature = "std")]
+mod calibrate;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod hasher;
+mod hex;
+mod inner;
+#[cfg(feature = "std")]
+mod io;
+mod merkle;
+#[cfg(feature = "tokio")]
+mod nonblocking;
+mod output;
+mod params;
+#[cfg(feature = "std")]
+mod pool;
+mod reverse;
+mod stack;
+mod state;
+mod stretch;
+#[cfg(feature = "testutil")]
+pub mod testutil;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(any(test, feature = "testutil"))]
+mod xorshift;
+
+pub use crate::builder::BlakeoutBuilder;
+pub use crate::error::Error;
+pub use crate::hasher::{BlakeoutHasher, BuildBlakeoutHasher};
+pub use crate::hex::{HexCase, from_hex, to_hex, to_hex_with};
+pub use crate::inner::InnerDigest;
+pub use crate::merkle::merkle_root;
+pub use crate::output::Output;
+pub use crate::params::Params;
+pub use crate::stack::BlakeoutStatic;
+pub use crate::state::StateBlob;
+pub use crate::stretch::stretch;
+#[cfg(feature = "std")]
+pub use crate::io::{Cancelled, HashingReader, HashingWriter, hash_file, hash_file_hex, hash_files, verify_file};
+#[cfg(feature = "std")]
+pub use crate::pool::{BlakeoutPool, thread_local_hash};
+#[cfg(feature = "mmap")]
+pub use crate::io::hash_file_mmap;
+#[cfg(feature = "tokio")]
+pub use crate::nonblocking::{hash_async, hash_file_async};
 
 const DEFAULT_HASH_SIZE: usize = 32;
 const DEFAULT_HASH_COUNT: usize = 65536;
+const MIN_HASH_COUNT: usize = 2;
+/// Number of previous scratchpad hashes used to get the next one
+const DEFAULT_LOOKBACK_BLOCKS: usize = 2;
+/// Maximum result size of all inner hash functions
+const MAX_RESULT_SIZE: usize = 64;
+/// How far ahead of the current hash the scratchpad is prefetched, in bytes
+const PREFETCH_DISTANCE: usize = 256;
+/// Size of chunks used to feed the scratchpad in reverse order
+const REVERSE_CHUNK_SIZE: usize = 1024;
+
+// Hashers and their results must stay usable across threads,
+// so interior mutability can't be added to them unnoticed
+const _: () = {
+    fn assert_send_sync<T: Send + Sync>() {}
+    #[allow(dead_code)]
+    fn assert_all() {
+        assert_send_sync::<Blakeout>();
+        assert_send_sync::<Blakeout2b>();
+        assert_send_sync::<BlakeoutBuilder>();
+        assert_send_sync::<BlakeoutHasher>();
+        #[cfg(feature = "std")]
+        assert_send_sync::<BlakeoutPool>();
+        assert_send_sync::<BlakeoutStatic<1024>>();
+        assert_send_sync::<Output>();
+        assert_send_sync::<Params>();
+        assert_send_sync::<StateBlob>();
+    }
+};
+
+/// Blakeout using Blake2b as inner hash, with results up to 64 bytes
+pub type Blakeout2b = Blakeout<VarBlake2b>;
+
+/// Blakeout using BLAKE3 as inner hash, with results up to 32 bytes.
+///
+/// It has the same scratchpad structure, but its results are unrelated to Blakeout
+/// with Blake2s, so it is not compatible with the original. Salt and personalization
+/// are not supported.
+#[cfg(feature = "blake3")]
+pub type Blakeout3 = Blakeout<blake3::Hasher>;
+
+/// Passes over the filled scratchpad used to get the result, see `BlakeoutBuilder::mode`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum BlakeoutMode {
+    /// The scratchpad is hashed forward and then in reverse order, as in the original algorithm
+    #[default]
+    DualPass,
+    /// The scratchpad is hashed only forward, it takes about a half of the final pass time.
+    ///
+    /// This mode is not memory hard: forward pass reads the hashes in the order they are
+    /// produced, so the result can be computed by keeping only the last `lookback_blocks`
+    /// hashes in memory. Only the time cost remains, use it only where memory hardness
+    /// is not needed, for example for verification of values hashed the same way.
+    ForwardOnly,
+}
 
-pub struct Blakeout {
+/// Memory hard hasher.
+///
+/// Every call to `update` only appends data to the message, the memory hard routine
+/// runs once over everything supplied since creation or the last `reset` when the result
+/// is needed: by `finalize`, `finalize_reset`, `compute` and similar methods. So splitting
+/// the input into several updates doesn't change the result or the cost.
+/// The whole message is kept in memory, use `BlakeoutBuilder::max_input` to limit it.
+///
+/// `result` and other `&self` accessors return the result of the last hashing,
+/// call `compute` after updates to bring it up to date.
+///
+/// Cloning makes a fully independent copy of the hasher, including its scratchpad,
+/// it can be used to continue a common prefix with different suffixes.
+///
+/// The inner hash function is Blake2s by default. Hashers with other inner functions,
+/// like `Blakeout2b`, are created by `new_inner` or `BlakeoutBuilder::build_inner`.
+///
+/// Hashers are `Send` and `Sync`: hashing needs `&mut self`, while reading the result
+/// by `result`, `result_str` and other `&self` methods never mutates the hasher,
+/// so it can be read from many threads at once. Use `BlakeoutPool` to share hashers.
+#[derive(Clone)]
+pub struct Blakeout<D: InnerDigest = VarBlake2s> {
     buffer: Vec<u8>,
-    result: Vec<u8>,
+    input: Vec<u8>,
+    result: [u8; MAX_RESULT_SIZE],
+    result_len: usize,
+    key: Vec<u8>,
+    salt: Vec<u8>,
+    personal: Vec<u8>,
+    domain: Vec<u8>,
+    hash_size: usize,
+    hash_count: usize,
+    lookback: usize,
+    mode: BlakeoutMode,
+    max_input: usize,
     dirty: bool,
+    inner: PhantomData<D>,
 }
 
 impl Default for Blakeout {
@@ -38,91 +235,935 @@ impl Default for Blakeout {
 }
 
 impl Blakeout {
+    /// Default size of result hash in bytes, usable in array lengths:
+    /// `[0u8; Blakeout::DEFAULT_OUTPUT_SIZE]`
+    pub const DEFAULT_OUTPUT_SIZE: usize = DEFAULT_HASH_SIZE;
+
     /// Creates new instance of Blakeout hasher
     pub fn new() -> Self {
-        let mut buffer = Vec::new();
-        buffer.resize(DEFAULT_HASH_SIZE * DEFAULT_HASH_COUNT, 0u8);
-        Blakeout { buffer, result: Vec::new(), dirty: false }
+        Self::new_inner()
+    }
+
+    /// Creates new instance of Blakeout hasher with custom scratchpad parameters.
+    ///
+    /// The scratchpad consists of `hash_count` hashes of `hash_size` bytes each,
+    /// `hash_size` is also the size of result hash. Defaults are 32 and 65536 (2 MB).
+    pub fn with_params(hash_size: usize, hash_count: usize) -> Result<Self, Error> {
+        BlakeoutBuilder::new().hash_size(hash_size).hash_count(hash_count).build()
+    }
+
+    /// Creates new instance of Blakeout hasher with scratchpad of `hash_count` 32 byte hashes,
+    /// `hash_count` must be a power of two and at least 2.
+    ///
+    /// It is useful for PoW schemes where difficulty scales with memory, use `with_params`
+    /// for arbitrary counts.
+    pub fn new_for_count(hash_count: usize) -> Result<Self, Error> {
+        if hash_count < MIN_HASH_COUNT {
+            return Err(Error::InvalidHashCount(hash_count));
+        }
+        if !hash_count.is_power_of_two() {
+            return Err(Error::HashCountNotPowerOfTwo(hash_count));
+        }
+        Self::with_params(DEFAULT_HASH_SIZE, hash_count)
+    }
+
+    /// Creates new hasher for interactive uses, like logins, with 2048 hashes (64 KB scratchpad).
+    ///
+    /// It is fast enough to hash on every request, but gives the least protection.
+    pub fn preset_interactive() -> Self {
+        Self::build(DEFAULT_HASH_SIZE, 2048)
+    }
+
+    /// Creates new hasher for sensitive data, like stored password hashes or key derivation,
+    /// with 65536 hashes (2 MB scratchpad), the same as `new`.
+    pub fn preset_sensitive() -> Self {
+        Self::build(DEFAULT_HASH_SIZE, DEFAULT_HASH_COUNT)
+    }
+
+    /// Creates new hasher for proof of work, like block hashes, with 524288 hashes
+    /// (16 MB scratchpad), to make specialized hardware for mining less efficient.
+    pub fn preset_pow() -> Self {
+        Self::build(DEFAULT_HASH_SIZE, 524288)
+    }
+
+    /// Creates new instance of keyed Blakeout hasher, it can be used as memory hard MAC.
+    ///
+    /// The key is passed to every inner Blake2s hasher and must be at most 32 bytes long.
+    /// Empty key gives the same results as unkeyed hasher.
+    pub fn new_keyed(key: &[u8]) -> Result<Self, Error> {
+        BlakeoutBuilder::new().key(key).build()
+    }
+
+    /// Creates new instance of Blakeout hasher with Blake2s salt and personalization,
+    /// they are set for every inner Blake2s hasher. Zero salt and personalization
+    /// give the same results as `new`.
+    pub fn new_with_salt(salt: &[u8; 8], personal: &[u8; 8]) -> Self {
+        BlakeoutBuilder::new()
+            .salt(salt)
+            .personal(personal)
+            .build()
+            .expect("8 byte salt and personalization are valid for Blake2s")
+    }
+
+    /// Creates new instance of Blakeout hasher separating its results by `domain`.
+    ///
+    /// The domain is hashed before the message, prefixed by its length as 64 bit
+    /// little-endian number, so the same message gives unrelated results in different
+    /// domains. Empty domain gives the same results as `new`.
+    pub fn new_with_domain(domain: &[u8]) -> Self {
+        let mut digest = Self::new();
+        digest.domain = domain.to_vec();
+        digest
+    }
+
+    /// Creates a builder to combine custom parameters, key, salt, personalization and domain
+    pub fn builder() -> BlakeoutBuilder {
+        BlakeoutBuilder::new()
+    }
+
+    /// Hashes supplied data with default parameters and returns the result
+    pub fn digest(data: impl AsRef<[u8]>) -> [u8; DEFAULT_HASH_SIZE] {
+        let mut digest = Blakeout::new();
+        digest.update(data);
+        digest.finalize()
+    }
+
+    /// Hashes supplied data with default parameters and returns the result as HEX string
+    pub fn digest_hex(data: impl AsRef<[u8]>) -> String {
+        to_hex(&Self::digest(data))
+    }
+
+    /// Same as `digest_hex`, but in upper case
+    pub fn digest_hex_upper(data: impl AsRef<[u8]>) -> String {
+        Self::digest_fmt(data, HexCase::Upper)
+    }
+
+    /// Hashes supplied data with default parameters and returns the result as HEX string in `case`
+    pub fn digest_fmt(data: impl AsRef<[u8]>, case: HexCase) -> String {
+        to_hex_with(&Self::digest(data), case == HexCase::Upper, None)
+    }
+
+    /// Returns the default size of result hash in bytes, see `output_len`
+    /// for the size configured in a hasher
+    pub const fn output_size() -> usize {
+        Self::DEFAULT_OUTPUT_SIZE
+    }
+
+    /// Returns the size of the scratchpad with default parameters in bytes (2 MB)
+    pub fn default_memory_cost() -> usize {
+        DEFAULT_HASH_SIZE * DEFAULT_HASH_COUNT
+    }
+
+    /// Returns result hash as `Output` value, filled with zeroes if nothing was hashed yet,
+    /// padded with zeroes if the hasher was created with `hash_size` less than 32 bytes
+    pub fn output(&self) -> Output {
+        let mut output = [0u8; DEFAULT_HASH_SIZE];
+        output[..self.result_len].copy_from_slice(self.result());
+        Output(output)
+    }
+
+    /// Same as `output`, but returns the array type of `digest` crate,
+    /// as used by `hmac`, `hkdf` and other RustCrypto crates
+    pub fn result_generic(&self) -> digest::Output<Self> {
+        let mut output = digest::Output::<Self>::default();
+        output.copy_from_slice(&self.output().0);
+        output
+    }
+
+    /// Consumes the hasher and returns result hash,
+    /// hashing an empty message if nothing was hashed yet.
+    ///
+    /// If the hasher was created with `hash_size` less than 32 bytes
+    /// the rest of returned array is filled with zeroes.
+    pub fn finalize(mut self) -> [u8; DEFAULT_HASH_SIZE] {
+        self.finalize_reset()
     }
 
-    /// Updates (hashes) supplied data
+    /// Returns result hash and resets the hasher for reuse
+    pub fn finalize_reset(&mut self) -> [u8; DEFAULT_HASH_SIZE] {
+        self.finish();
+        let mut result = [0u8; DEFAULT_HASH_SIZE];
+        result[..self.hash_size].copy_from_slice(self.result());
+        result
+    }
+}
+
+impl<D: InnerDigest> Blakeout<D> {
+    /// Creates new instance of Blakeout hasher with inner hash function `D`
+    /// and default parameters, the result size is the maximum output size of `D`
+    pub fn new_inner() -> Self {
+        Self::build(D::MAX_OUTPUT_SIZE, DEFAULT_HASH_COUNT)
+    }
+
+    /// The scratchpad is not allocated until the first hashing
+    fn build(hash_size: usize, hash_count: usize) -> Self {
+        Blakeout {
+            buffer: Vec::new(),
+            input: Vec::new(),
+            result: [0u8; MAX_RESULT_SIZE],
+            result_len: 0,
+            key: Vec::new(),
+            salt: Vec::new(),
+            personal: Vec::new(),
+            domain: Vec::new(),
+            hash_size,
+            hash_count,
+            lookback: DEFAULT_LOOKBACK_BLOCKS,
+            mode: BlakeoutMode::DualPass,
+            max_input: usize::MAX,
+            dirty: false,
+            inner: PhantomData,
+        }
+    }
+
+    /// Appends supplied data to the message, it is hashed once by `finalize` or `compute`.
+    /// Panics if the message would be longer than `BlakeoutBuilder::max_input`.
     pub fn update(&mut self, data: impl AsRef<[u8]>) {
-        self.process_input(data.as_ref());
+        self.try_update(data).expect("message is longer than max_input");
+    }
+
+    /// Same as `update`, but returns `Error::InputTooLarge` instead of panicking
+    /// if the message would be longer than `BlakeoutBuilder::max_input`.
+    /// The message is left unchanged in that case.
+    pub fn try_update(&mut self, data: impl AsRef<[u8]>) -> Result<(), Error> {
+        let data = data.as_ref();
+        self.check_input(data.len())?;
+        self.append(data);
+        Ok(())
+    }
+
+    /// Appends `s` with ASCII letters lowercased to the message,
+    /// so identifiers like emails hash the same in any case. Non-ASCII characters are
+    /// passed unchanged, normalize them before if needed.
+    /// Panics if the message would be longer than `BlakeoutBuilder::max_input`.
+    pub fn update_str_ci(&mut self, s: &str) {
+        self.try_update_str_ci(s).expect("message is longer than max_input");
+    }
+
+    /// Same as `update_str_ci`, but returns `Error::InputTooLarge` instead of panicking,
+    /// the message is left unchanged in that case
+    pub fn try_update_str_ci(&mut self, s: &str) -> Result<(), Error> {
+        self.check_input(s.len())?;
+        self.input.extend(s.bytes().map(|b| b.to_ascii_lowercase()));
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Appends supplied data prefixed by its length as 64 bit little-endian number
+    /// to the message.
+    ///
+    /// Framed fields can't be confused, so `["ab", "c"]` and `["a", "bc"]` give different
+    /// results. Data fed by plain `update` has no framing, mixing them in one message
+    /// is up to the caller. Panics if the message would be longer than `BlakeoutBuilder::max_input`.
+    pub fn update_framed(&mut self, data: impl AsRef<[u8]>) {
+        self.try_update_framed(data).expect("message is longer than max_input");
+    }
+
+    /// Same as `update_framed`, but returns `Error::InputTooLarge` instead of panicking,
+    /// the message is left unchanged in that case
+    pub fn try_update_framed(&mut self, data: impl AsRef<[u8]>) -> Result<(), Error> {
+        let data = data.as_ref();
+        self.try_update_all([&(data.len() as u64).to_le_bytes()[..], data])
+    }
+
+    /// Appends all chunks to the message in order,
+    /// it gives the same result as `update` with the concatenation of the chunks.
+    /// Panics if the message would be longer than `BlakeoutBuilder::max_input`.
+    pub fn update_all<I, B>(&mut self, chunks: I)
+    where
+        I: IntoIterator<Item = B>,
+        B: AsRef<[u8]>,
+    {
+        self.try_update_all(chunks).expect("message is longer than max_input");
+    }
+
+    /// Same as `update_all`, but returns `Error::InputTooLarge` instead of panicking,
+    /// the message is left unchanged in that case, without any of the chunks
+    pub fn try_update_all<I, B>(&mut self, chunks: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = B>,
+        B: AsRef<[u8]>,
+    {
+        let len = self.input.len();
+        let dirty = self.dirty;
+        for chunk in chunks {
+            let chunk = chunk.as_ref();
+            if let Err(e) = self.check_input(chunk.len()) {
+                self.input.truncate(len);
+                self.dirty = dirty;
+                return Err(e);
+            }
+            self.append(chunk);
+        }
+        Ok(())
+    }
+
+    /// Appends data to the message without hashing it.
+    /// The result becomes outdated, so the hasher is marked clean and `finalize`
+    /// will hash the accumulated message.
+    /// The length must be checked by `check_input` before.
+    fn append(&mut self, data: &[u8]) {
+        self.input.extend_from_slice(data);
+        self.dirty = false;
+    }
+
+    /// Checks that `len` more bytes fit in the message limit
+    fn check_input(&self, len: usize) -> Result<(), Error> {
+        match self.input.len().checked_add(len) {
+            Some(total) if total <= self.max_input => Ok(()),
+            _ => Err(Error::InputTooLarge),
+        }
+    }
+
+    /// Deliberately folds previous result (if any) into the hash of supplied data,
+    /// the new result is the hash of previous result followed by `data`.
+    ///
+    /// Calling it repeatedly gives iterated hashing, for example rounds of key stretching,
+    /// unlike `update`, which never uses previous results. Unlike `update` it hashes
+    /// right away, so every call costs a full memory hard hashing. A message accumulated
+    /// by `update` before is hashed first and its result is folded, then the message
+    /// is discarded, so a following `update` starts a new message. Use `reset`
+    /// to start a new chain.
+    ///
+    /// If `digest::Update` is in scope, its `chain` taking the hasher by value is picked
+    /// by method call syntax, call this one as `Blakeout::chain(&mut hasher, data)`.
+    /// Panics if `data` is longer than `BlakeoutBuilder::max_input`.
+    pub fn chain(&mut self, data: impl AsRef<[u8]>) {
+        self.try_chain(data).expect("message is longer than max_input");
     }
 
-    /// Resets current dirty state to start over
+    /// Same as `chain`, but returns `Error::InputTooLarge` instead of panicking,
+    /// the hasher and its message are left unchanged in that case
+    pub fn try_chain(&mut self, data: impl AsRef<[u8]>) -> Result<(), Error> {
+        let data = data.as_ref();
+        if data.len() > self.max_input {
+            return Err(Error::InputTooLarge);
+        }
+        self.compute_pending();
+        self.input.clear();
+        self.input.extend_from_slice(data);
+        let result = self.process_input(true);
+        self.input.clear();
+        result.expect("invalid hasher parameters");
+        Ok(())
+    }
+
+    /// Same as `chain`, this is the behaviour `update` had before it became accumulating,
+    /// it is kept for compatibility with hashes computed by older versions
+    pub fn update_chained(&mut self, data: impl AsRef<[u8]>) {
+        self.chain(data);
+    }
+
+    /// Folds the result of `other` into this hasher, order-dependent:
+    /// the new result is the hash of this result followed by the result of `other`,
+    /// so `a.combine(&b)` and `b.combine(&a)` differ.
+    ///
+    /// A pending message of this hasher is hashed first, as by `chain`, while `other`
+    /// can't be computed here, so only its last computed result is used.
+    pub fn combine(&mut self, other: &Self) {
+        self.compute_pending();
+        let mut input = Vec::with_capacity(self.result().len() + other.result().len());
+        input.extend_from_slice(self.result());
+        input.extend_from_slice(other.result());
+        self.hash_results(input);
+    }
+
+    /// Folds the result of `other` into this hasher, order-independent:
+    /// the new result is the hash of both results concatenated in ascending
+    /// lexicographic order, so `a.combine_commutative(&b)` and `b.combine_commutative(&a)`
+    /// give the same result. Pending messages are treated as by `combine`.
+    pub fn combine_commutative(&mut self, other: &Self) {
+        self.compute_pending();
+        let (first, second) = if self.result() <= other.result() {
+            (self.result(), other.result())
+        } else {
+            (other.result(), self.result())
+        };
+        let mut input = Vec::with_capacity(first.len() + second.len());
+        input.extend_from_slice(first);
+        input.extend_from_slice(second);
+        self.hash_results(input);
+    }
+
+    /// Hashes the accumulated message if it was updated since the last hashing
+    fn compute_pending(&mut self) {
+        if !self.dirty && !self.input.is_empty() {
+            self.process_input(false).expect("invalid hasher parameters");
+        }
+    }
+
+    /// Replaces the result by the hash of concatenated results in `input`
+    fn hash_results(&mut self, input: Vec<u8>) {
+        self.input = input;
+        let result = self.process_input(false);
+        self.input.clear();
+        result.expect("invalid hasher parameters");
+    }
+
+    /// Resets current dirty state and accumulated message to start over.
+    ///
+    /// This is cheap: the previous result stays readable through `result` until the next
+    /// hashing, and the scratchpad keeps its allocation and stale contents for reuse.
+    /// Neither affects next hashes, use `clear` if they must be wiped.
     pub fn reset(&mut self) {
+        self.input.clear();
+        self.dirty = false;
+    }
+
+    /// Resets the hasher, forgets the result and changes its scratchpad parameters, keeping the key,
+    /// salt, personalization and domain. The parameters are checked as by `with_params`,
+    /// the hasher is left unchanged if they are invalid.
+    ///
+    /// An allocated scratchpad is reused when the new one is not larger, so shrinking
+    /// never reallocates, and growing extends the existing allocation.
+    pub fn reset_with_params(&mut self, hash_size: usize, hash_count: usize) -> Result<(), Error> {
+        let size = builder::validate_size::<D>(hash_size, hash_count)?;
+        self.reset();
+        self.result = [0u8; MAX_RESULT_SIZE];
+        self.result_len = 0;
+        if !self.buffer.is_empty() {
+            self.buffer.resize(size, 0u8);
+        }
+        self.hash_size = hash_size;
+        self.hash_count = hash_count;
+        Ok(())
+    }
+
+    /// Resets the hasher to the state of a freshly created one.
+    ///
+    /// Unlike `reset` it also zeroes the result, the accumulated message and the scratchpad
+    /// before forgetting them. The scratchpad stays allocated for reuse.
+    pub fn clear(&mut self) {
+        for x in self.input.iter_mut().chain(self.result.iter_mut()).chain(self.buffer.iter_mut()) {
+            *x = 0;
+        }
+        self.input.clear();
+        self.result_len = 0;
         self.dirty = false;
     }
 
-    /// Returns the size of result hash in bytes
-    pub fn output_size() -> usize {
-        DEFAULT_HASH_SIZE
+    /// Returns `true` if something was hashed since creation or the last `reset`
+    /// and the result is the hash of the accumulated message, `update` makes it outdated.
+    ///
+    /// The result of a dirty hasher is prepended to the data by `chain`,
+    /// while a clean hasher starts the chain from scratch. Plain `update` doesn't
+    /// depend on this state, it always hashes the whole accumulated message.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Returns the size of the scratchpad in bytes, that the hasher allocates on first hashing
+    pub fn memory_cost(&self) -> usize {
+        self.hash_size.saturating_mul(self.hash_count)
+    }
+
+    /// Returns the configured size of result hash in bytes, `result` has this size
+    /// after hashing and the rest of `finalize` output is filled with zeroes
+    pub fn output_len(&self) -> usize {
+        self.hash_size
     }
 
-    /// Returns a slice of result hash, can be used multiple times
+    /// Returns a slice of result hash of the last hashing, can be used multiple times.
+    ///
+    /// `update` doesn't hash, so the result is valid only after `compute`, `chain`
+    /// or finalizing, and it is outdated by any next update, see `is_dirty`.
+    /// It is empty if nothing was hashed yet, it is not a hash of an empty message.
+    /// Use `try_result` to get an error in that case, or `compute` to get the hash
+    /// of the accumulated message, even an empty one.
     pub fn result(&self) -> &[u8] {
-        &self.result
+        &self.result[..self.result_len]
+    }
+
+    /// Consumes the hasher and returns result hash as a vector, empty if nothing was hashed yet.
+    ///
+    /// The scratchpad is freed right away (and wiped with `zeroize` feature), unlike keeping
+    /// the hasher around after `result().to_vec()`.
+    pub fn into_result_vec(self) -> Vec<u8> {
+        self.result().to_vec()
+    }
+
+    /// Returns a slice of result hash or `Error::NotHashed` if nothing was hashed yet,
+    /// use `try_result().ok()` to get an `Option`
+    pub fn try_result(&self) -> Result<&[u8], Error> {
+        if self.result_len == 0 {
+            return Err(Error::NotHashed);
+        }
+        Ok(self.result())
+    }
+
+    /// Copies up to `out.len()` bytes of result hash to `out` and returns the number of copied bytes.
+    ///
+    /// If `out` is shorter than the result, only its first `out.len()` bytes are copied,
+    /// if it is longer, the rest of `out` is left untouched. Copies nothing if nothing was hashed yet.
+    pub fn copy_result_into(&self, out: &mut [u8]) -> usize {
+        let len = self.result_len.min(out.len());
+        out[..len].copy_from_slice(&self.result[..len]);
+        len
+    }
+
+    /// Compares result hash with `expected` in constant time, use it to check passwords or MACs.
+    ///
+    /// Returns `false` if the lengths differ or the result doesn't cover the accumulated
+    /// message, i.e. nothing was hashed since the last `update` or `reset`, see `is_dirty`.
+    /// Unlike `==`, the time doesn't depend on the position of the first differing byte:
+    ///
+    /// ```rust
+    /// use blakeout::BlakeoutBuilder;
+    ///
+    /// let mut mac = BlakeoutBuilder::new().hash_count(1024).key(b"secret").build().unwrap();
+    /// mac.update(b"message");
+    /// let received = mac.compute().to_vec();
+    /// // The tag is secret until checked, compare it in constant time
+    /// assert!(mac.verify(&received));
+    /// ```
+    pub fn verify(&self, expected: &[u8]) -> bool {
+        self.dirty && ct_eq(self.result(), expected)
+    }
+
+    /// Parses `expected_hex` in upper or lower case and compares it with result hash
+    /// in constant time as by `verify`, for checksums stored as text.
+    ///
+    /// Returns `Error::InvalidHex` or `Error::InvalidLength` if `expected_hex` is not valid HEX,
+    /// `Ok(false)` if it is valid, but doesn't match.
+    pub fn verify_hex(&self, expected_hex: &str) -> Result<bool, Error> {
+        let expected = from_hex(expected_hex)?;
+        Ok(self.verify(&expected))
     }
 
     /// Converts the result hash to a String and returns it
     pub fn result_str(&self) -> String {
-        to_hex(&self.result)
+        to_hex(self.result())
+    }
+
+    /// Writes the result hash in lowercase HEX to `w` without allocating a String,
+    /// writes nothing if nothing was hashed yet
+    pub fn write_hex_to<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        hex::write_hex(w, self.result())
+    }
+
+    /// Converts the result hash to an uppercase String and returns it
+    pub fn result_str_upper(&self) -> String {
+        to_hex_with(self.result(), true, None)
     }
 
-    fn process_input(&mut self, data: &[u8]) {
-        let hash_size = DEFAULT_HASH_SIZE;
-        let hash_count = self.buffer.len() / hash_size;
-        let mut digest = Blake2s::default();
+    /// Returns result hash in Base64 with standard alphabet (`A-Z`, `a-z`, `0-9`, `+`, `/`)
+    /// and `=` padding, as in RFC 4648, empty string if nothing was hashed yet
+    #[cfg(feature = "base64")]
+    pub fn result_base64(&self) -> String {
+        use base64::Engine;
+        base64::engine::general_purpose::STANDARD.encode(self.result())
+    }
+
+    /// Returns result hash in Base58 with Bitcoin alphabet
+    /// (`1-9`, `A-Z`, `a-z` without `0`, `O`, `I` and `l`), empty string if nothing was hashed yet
+    #[cfg(feature = "bs58")]
+    pub fn result_base58(&self) -> String {
+        bs58::encode(self.result()).into_string()
+    }
 
-        if self.dirty {
-            digest.update(&self.result);
+    /// Fills `out` with output of any length derived from result hash,
+    /// hashing the accumulated message if the result is outdated.
+    ///
+    /// Output consists of consecutive blocks of inner hash maximum size (32 bytes for Blake2s),
+    /// block `i` is inner hash of `result || i`, where `i` is a 64 bit little-endian counter
+    /// starting from zero, and the last block is truncated to fit. So the output doesn't
+    /// contain the result itself, but shorter outputs are always prefixes of longer ones.
+    /// The hasher state is kept, so it can be called again.
+    pub fn finalize_xof(&mut self, out: &mut [u8]) {
+        self.compute();
+        let mut digest = D::new(D::MAX_OUTPUT_SIZE, &[], &[], &[]);
+        let mut hash = [0u8; MAX_RESULT_SIZE];
+        let hash = &mut hash[..D::MAX_OUTPUT_SIZE];
+        for (counter, block) in out.chunks_mut(D::MAX_OUTPUT_SIZE).enumerate() {
+            digest.update(self.result());
+            digest.update(&(counter as u64).to_le_bytes());
+            digest.finalize_to(hash);
+            block.copy_from_slice(&hash[..block.len()]);
         }
-        // Preparing the scratchpad
-        digest.update(data);
-        Self::finalize_to(digest, &mut self.buffer.as_mut_slice()[0..hash_size]);
-        let double_size = hash_size * 2;
-        for x in (hash_size..hash_size * hash_count).step_by(hash_size) {
-            let mut digest = Blake2s::default();
-            let start = if x >= double_size { x - double_size } else { 0 };
-            digest.update(&self.buffer[start..x]);
-            Self::finalize_to(digest, &mut self.buffer.as_mut_slice()[x..(x + hash_size)]);
-        }
-        // Hashing whole buffer one way and another
-        let mut digest = Blake2s::default();
-        digest.update(&self.buffer);
-        self.buffer.reverse();
-        digest.update(&self.buffer);
-        self.result.resize(DEFAULT_HASH_SIZE, 0u8);
-        Self::finalize_to(digest, self.result.as_mut_slice());
+    }
+
+    /// Consumes the hasher and writes result hash to `out`, hashing an empty message
+    /// if nothing was hashed yet. Returns `Error::InvalidLength` without hashing
+    /// if the length of `out` is not `output_len`.
+    pub fn finalize_into_slice(mut self, out: &mut [u8]) -> Result<(), Error> {
+        if out.len() != self.hash_size {
+            return Err(Error::InvalidLength(out.len()));
+        }
+        self.finish();
+        out.copy_from_slice(self.result());
+        Ok(())
+    }
+
+    /// Hashes the accumulated message if the result is outdated and returns result hash,
+    /// without resetting the hasher, so more data can be appended after it.
+    ///
+    /// It hashes an empty message if nothing was supplied yet. The result stays readable
+    /// by `result` and other accessors until the next update.
+    pub fn compute(&mut self) -> &[u8] {
+        if !self.dirty {
+            self.process_input(false).expect("invalid hasher parameters");
+        }
+        self.result()
+    }
+
+    /// Hashes the message if the result is outdated and resets the hasher,
+    /// the result stays readable until the next hashing
+    fn finish(&mut self) {
+        self.compute();
+        self.reset();
+    }
+
+    /// Hashes the accumulated message, prepending previous result if `chained` is set
+    fn process_input(&mut self, chained: bool) -> Result<(), Error> {
+        let hash_size = self.hash_size;
+        let mut digest = self.inner_digest()?;
+        if self.buffer.is_empty() {
+            self.buffer.resize(buffer_size(hash_size, self.hash_count)?, 0u8);
+        }
+
+        if !self.domain.is_empty() {
+            digest.update(&(self.domain.len() as u64).to_le_bytes());
+            digest.update(&self.domain);
+        }
+        if chained && self.dirty {
+            digest.update(self.result());
+        }
+        digest.update(&self.input);
+        hash_scratchpad(&mut digest, &mut self.buffer, hash_size, self.lookback, self.mode, &mut self.result[..hash_size]);
+        self.result_len = hash_size;
         self.dirty = true;
+        Ok(())
+    }
+
+    /// Creates inner hasher with configured output size, key, salt and personalization
+    fn inner_digest(&self) -> Result<D, Error> {
+        if self.hash_size == 0 || self.hash_size > D::MAX_OUTPUT_SIZE {
+            return Err(Error::InvalidOutputSize);
+        }
+        Ok(D::new(self.hash_size, &self.key, &self.salt, &self.personal))
+    }
+}
+
+/// Writes result hash in HEX format, nothing if nothing was hashed yet
+impl<D: InnerDigest> fmt::Display for Blakeout<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_hex_to(f)
+    }
+}
+
+/// Returns result hash, the same as `result`
+impl<D: InnerDigest> AsRef<[u8]> for Blakeout<D> {
+    fn as_ref(&self) -> &[u8] {
+        self.result()
+    }
+}
+
+/// Shows the state and the result, but never the key, the message or the scratchpad.
+/// The result of a keyed hasher is a MAC, so it is redacted, get it by `finalize`.
+impl<D: InnerDigest> fmt::Debug for Blakeout<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.key.is_empty() {
+            return f.debug_struct("Blakeout")
+                .field("keyed", &true)
+                .field("result", &format_args!("<redacted until finalized>"))
+                .finish();
+        }
+        f.debug_struct("Blakeout")
+            .field("dirty", &self.dirty)
+            .field("result", &self.result_str())
+            .finish()
+    }
+}
+
+/// Compares the results only, the scratchpads and accumulated messages are ignored.
+///
+/// The comparison is not in constant time, it returns as soon as a byte differs.
+/// It is fine for public values like checksums and PoW hashes,
+/// use `verify` to compare secrets like MACs and password hashes.
+///
+/// ```rust
+/// use blakeout::Blakeout;
+///
+/// let mut hasher = Blakeout::with_params(32, 1024).unwrap();
+/// hasher.update(b"file contents");
+/// let checksum = hasher.compute().to_vec();
+/// // Checksums are public, a variable time comparison is fine
+/// assert!(hasher == checksum[..]);
+/// ```
+///
+/// Two hashers that haven't hashed anything are equal, as both have empty results.
+/// Only the last computed results are compared: `reset` doesn't clear the result and `update`
+/// doesn't hash, so a hasher with a pending message is equal to its state before `update`
+/// or `reset`, call `compute` first or use `verify`, which rejects such results.
+impl<D: InnerDigest> PartialEq for Blakeout<D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.result() == other.result()
+    }
+}
+
+impl<D: InnerDigest> Eq for Blakeout<D> {}
+
+/// Compares the last computed result hash to expected bytes, not in constant time,
+/// use `verify` for secrets
+impl<D: InnerDigest> PartialEq<[u8]> for Blakeout<D> {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.result() == other
+    }
+}
+
+/// Same as `PartialEq<[u8]>`, not in constant time
+impl<D: InnerDigest> PartialEq<&[u8]> for Blakeout<D> {
+    fn eq(&self, other: &&[u8]) -> bool {
+        self.result() == *other
+    }
+}
+
+/// Same as `PartialEq<[u8]>`, not in constant time
+impl<D: InnerDigest> PartialEq<[u8; DEFAULT_HASH_SIZE]> for Blakeout<D> {
+    fn eq(&self, other: &[u8; DEFAULT_HASH_SIZE]) -> bool {
+        self.result() == other
+    }
+}
+
+/// Wipes the scratchpad, the message, the result and the key, they can contain secrets
+#[cfg(feature = "zeroize")]
+impl<D: InnerDigest> Drop for Blakeout<D> {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+        self.buffer.zeroize();
+        self.input.zeroize();
+        self.result.zeroize();
+        self.key.zeroize();
+        self.salt.zeroize();
+        self.personal.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<D: InnerDigest> zeroize::ZeroizeOnDrop for Blakeout<D> {}
+
+impl<D: InnerDigest> HashMarker for Blakeout<D> {}
+
+impl OutputSizeUser for Blakeout {
+    type OutputSize = U32;
+}
+
+/// Block size of inner Blake2s, it is needed by HMAC
+impl BlockSizeUser for Blakeout {
+    type BlockSize = U64;
+}
+
+/// `Update` is object safe, so code that only feeds bytes can take `&mut dyn Update`
+/// and drive any hasher without being generic over it:
+///
+/// ```rust
+/// use blakeout::Blakeout;
+/// use digest::Update;
+///
+/// fn feed(hasher: &mut dyn Update) {
+///     hasher.update(b"hello ");
+///     hasher.update(b"world");
+/// }
+///
+/// let mut hasher = Blakeout::new();
+/// feed(&mut hasher);
+/// assert_eq!(Blakeout::digest(b"hello world"), hasher.finalize());
+/// ```
+impl<D: InnerDigest> Update for Blakeout<D> {
+    fn update(&mut self, data: &[u8]) {
+        Blakeout::update(self, data);
     }
+}
+
+impl FixedOutput for Blakeout {
+    fn finalize_into(self, out: &mut digest::Output<Self>) {
+        out.copy_from_slice(&Blakeout::finalize(self));
+    }
+}
+
+impl FixedOutputReset for Blakeout {
+    fn finalize_into_reset(&mut self, out: &mut digest::Output<Self>) {
+        out.copy_from_slice(&Blakeout::finalize_reset(self));
+    }
+}
+
+impl<D: InnerDigest> Reset for Blakeout<D> {
+    fn reset(&mut self) {
+        Blakeout::reset(self);
+    }
+}
+
+/// Hashes every input independently and returns the results in the same order.
+///
+/// With `rayon` feature enabled the inputs are distributed across rayon's thread pool,
+/// every thread uses its own scratchpad.
+pub fn hash_many<T: AsRef<[u8]> + Sync>(inputs: &[T]) -> Vec<[u8; DEFAULT_HASH_SIZE]> {
+    let hash = |digest: &mut Blakeout, data: &T| {
+        digest.update(data);
+        digest.finalize_reset()
+    };
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        inputs.par_iter().map_init(Blakeout::new, hash).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        let mut digest = Blakeout::new();
+        inputs.iter().map(|data| hash(&mut digest, data)).collect()
+    }
+}
+
+/// Hashes the chunks one by one by `Blakeout::chain` with default parameters and yields
+/// the digest after every chunk.
+///
+/// The first digest is the hash of the first chunk, every next one is the hash of
+/// the previous digest followed by the next chunk, so every digest commits to all chunks
+/// before it. The chunks are hashed lazily, as the iterator is advanced.
+pub fn hash_stream<I, B>(chunks: I) -> impl Iterator<Item = [u8; DEFAULT_HASH_SIZE]>
+where
+    I: IntoIterator<Item = B>,
+    B: AsRef<[u8]>,
+{
+    let mut digest = Blakeout::new();
+    chunks.into_iter().map(move |chunk| {
+        Blakeout::chain(&mut digest, chunk);
+        digest.output().0
+    })
+}
+
+/// Hashes a set of items with default parameters so that the result does not depend
+/// on their order.
+///
+/// Every item is hashed independently as by `hash_many`, the item digests are sorted
+/// in ascending lexicographic order, concatenated and the concatenation is hashed
+/// again. Sorting is used instead of XOR so that repeated items are not cancelled out:
+/// the input is treated as a multiset, `[a, a]` and `[a]` give different digests.
+pub fn set_digest<T: AsRef<[u8]> + Sync>(items: &[T]) -> [u8; DEFAULT_HASH_SIZE] {
+    let mut digests = hash_many(items);
+    digests.sort_unstable();
+    Blakeout::digest(digests.concat())
+}
+
+/// Fills the scratchpad by `hash_size` hashes, starting from the message already fed
+/// to `digest`, every hash is the hash of `lookback` previous ones, and writes the hash of the whole scratchpad to `result`
+fn hash_scratchpad<D: InnerDigest>(digest: &mut D, buffer: &mut [u8], hash_size: usize, lookback: usize, mode: BlakeoutMode, result: &mut [u8]) {
+    // Preparing the scratchpad
+    digest.finalize_to(&mut buffer[0..hash_size]);
+    let window = lookback.saturating_mul(hash_size);
+    for x in (hash_size..buffer.len()).step_by(hash_size) {
+        let start = x.saturating_sub(window);
+        prefetch(buffer.as_ptr().wrapping_add(x + PREFETCH_DISTANCE));
+        digest.update(&buffer[start..x]);
+        digest.finalize_to(&mut buffer[x..(x + hash_size)]);
+    }
+    // Hashing whole buffer one way and another, the reversed bytes are fed
+    // by small chunks to keep the buffer untouched
+    digest.update(buffer);
+    if mode == BlakeoutMode::ForwardOnly {
+        digest.finalize_to(result);
+        return;
+    }
+    let mut reversed = [0u8; REVERSE_CHUNK_SIZE];
+    for chunk in buffer.rchunks(REVERSE_CHUNK_SIZE) {
+        let reversed = &mut reversed[..chunk.len()];
+        reverse::reverse_into(reversed, chunk);
+        digest.update(reversed);
+    }
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(&mut reversed);
+    digest.finalize_to(result);
+}
 
-    fn finalize_to(digest: Blake2s, slice: &mut[u8]) {
-        let buf = Digest::finalize(digest);
-        slice.copy_from_slice(&buf[..]);
+/// Hints the processor to load the cache line at `ptr` for writing soon,
+/// it never faults, so `ptr` may point outside of the scratchpad
+#[inline(always)]
+fn prefetch(ptr: *const u8) {
+    // SSE is always present on x86_64, so no runtime detection is needed
+    #[cfg(all(feature = "prefetch", target_arch = "x86_64"))]
+    unsafe {
+        use core::arch::x86_64::{_MM_HINT_T0, _mm_prefetch};
+        _mm_prefetch::<_MM_HINT_T0>(ptr as *const i8);
     }
+    #[cfg(not(all(feature = "prefetch", target_arch = "x86_64")))]
+    let _ = ptr;
 }
 
-/// Convert bytes array to HEX format
-fn to_hex(buf: &[u8]) -> String {
-    let mut result = String::new();
-    for x in buf.iter() {
-        result.push_str(&format!("{:01$x}", x, 2));
+/// Returns scratchpad size in bytes, making sure it can be allocated
+fn buffer_size(hash_size: usize, hash_count: usize) -> Result<usize, Error> {
+    match hash_size.checked_mul(hash_count) {
+        Some(size) if size <= isize::MAX as usize => Ok(size),
+        _ => Err(Error::BufferTooLarge),
+    }
+}
+
+/// Compares two slices in time independent of their contents
+fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    #[cfg(feature = "subtle")]
+    {
+        use subtle::ConstantTimeEq;
+        a.ct_eq(b).into()
+    }
+    #[cfg(not(feature = "subtle"))]
+    {
+        if a.len() != b.len() {
+            return false;
+        }
+        a.iter().zip(b.iter()).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
     }
-    result
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{Blakeout, to_hex};
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use std::io::{Cursor, Read};
+    use blake2::VarBlake2s;
+    use digest::Digest;
+    use digest09::{Update, VariableOutput};
+    use hmac::{Mac, SimpleHmac};
+    use crate::{Blakeout, Blakeout2b, BlakeoutBuilder, Error, HexCase, VarBlake2b, hash_many, to_hex};
+    use crate::xorshift::XorShift;
     const DATA: &[u8; 29] = b"Science is poetry of reality!";
 
+    /// Generic reading loop feeding any `Digest` by blocks of up to 1 KB until EOF
+    fn process<D: Digest + Default, R: Read>(reader: &mut R) -> Vec<u8> {
+        let mut sh = D::default();
+        let mut buffer = [0u8; 1024];
+        loop {
+            let n = reader.read(&mut buffer).unwrap();
+            if n == 0 {
+                break;
+            }
+            sh.update(&buffer[..n]);
+        }
+        sh.finalize().to_vec()
+    }
+
+    /// Straightforward implementation of the algorithm to check optimizations against
+    fn reference(data: &[u8], hash_size: usize, hash_count: usize) -> Vec<u8> {
+        let hash = |data: &[u8]| {
+            let mut digest = VarBlake2s::new(hash_size).unwrap();
+            digest.update(data);
+            digest.finalize_boxed().to_vec()
+        };
+        let mut buffer = hash(data);
+        for x in 1..hash_count {
+            let start = x.saturating_sub(2) * hash_size;
+            let next = hash(&buffer[start..x * hash_size]);
+            buffer.extend_from_slice(&next);
+        }
+        let mut whole = buffer.clone();
+        whole.extend(buffer.iter().rev());
+        hash(&whole)
+    }
+
     #[test]
     fn single_input() {
         let mut digest = Blakeout::default();
         digest.update(DATA);
-        assert_eq!("4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62", to_hex(&digest.result()));
+        digest.compute();
+        assert_eq!("4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62", to_hex(digest.result()));
     }
 
     #[test]
@@ -130,18 +1171,1051 @@ mod tests {
         let mut digest = Blakeout::default();
         digest.update(DATA);
         digest.update(DATA);
-        assert_eq!("a1b6cd16c9e718b876afb7bf4d61b64291a98a3dea0f20731da663b0358e68b9", to_hex(&digest.result()));
+        digest.compute();
+        let mut whole = Blakeout::default();
+        whole.update([&DATA[..], &DATA[..]].concat());
+        whole.compute();
+        assert_eq!(whole.result(), digest.result());
     }
 
     #[test]
-    fn test_reset() {
+    fn split_input() {
+        let mut digest = Blakeout::default();
+        digest.update(&DATA[..7]);
+        digest.update(&DATA[7..20]);
+        digest.update(&DATA[20..]);
+        digest.compute();
+        assert_eq!("4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62", digest.result_str());
+    }
+
+    #[test]
+    fn chained_input() {
         let mut digest = Blakeout::default();
+        digest.update_chained(DATA);
+        digest.update_chained(DATA);
+        assert_eq!("a1b6cd16c9e718b876afb7bf4d61b64291a98a3dea0f20731da663b0358e68b9", to_hex(digest.result()));
+
+        // A message accumulated by updates is hashed before chaining
+        let mut pending = Blakeout::default();
+        pending.update(DATA);
+        pending.chain(DATA);
+        assert_eq!(digest.result(), pending.result());
+    }
+
+    #[test]
+    fn update_str_ci() {
+        let hash = |s: &str, ci: bool| {
+            let mut digest = Blakeout::with_params(32, 1024).unwrap();
+            if ci {
+                digest.update_str_ci(s);
+            } else {
+                digest.update(s);
+            }
+            digest.finalize()
+        };
+        assert_eq!(hash("abc", true), hash("ABC", true));
+        assert_eq!(hash("abc", false), hash("AbC", true));
+        assert_ne!(hash("abc", false), hash("ABC", false));
+        assert_eq!(hash("ÄBC", true), hash("Äbc", false));
+        assert_ne!(hash("ÄBC", true), hash("äbc", false));
+    }
+
+    #[test]
+    fn finalize_into_slice() {
+        let mut digest = Blakeout::with_params(16, 1024).unwrap();
         digest.update(DATA);
-        let hash1 = digest.result_str();
-        digest.reset();
+        let mut exact = [0u8; 16];
+        digest.clone().finalize_into_slice(&mut exact).unwrap();
+        assert_eq!("aaed56d401021ba165a1e2d1ef91b29a", to_hex(&exact));
+        let mut short = [0u8; 15];
+        assert_eq!(Err(Error::InvalidLength(15)), digest.clone().finalize_into_slice(&mut short));
+        let mut long = [0u8; 32];
+        assert_eq!(Err(Error::InvalidLength(32)), digest.finalize_into_slice(&mut long));
+        assert_eq!([0u8; 32], long);
+
+        let mut empty = [0u8; 32];
+        Blakeout::new().finalize_into_slice(&mut empty).unwrap();
+        assert_eq!(Blakeout::digest([]), empty);
+    }
+
+    #[test]
+    fn update_framed() {
+        let hash = |fields: &[&[u8]], framed: bool| {
+            let mut digest = Blakeout::with_params(32, 1024).unwrap();
+            for field in fields {
+                if framed {
+                    digest.update_framed(field);
+                } else {
+                    digest.update(field);
+                }
+            }
+            digest.finalize()
+        };
+        assert_eq!(hash(&[b"ab", b"c"], false), hash(&[b"a", b"bc"], false));
+        assert_ne!(hash(&[b"ab", b"c"], true), hash(&[b"a", b"bc"], true));
+
+        let mut digest = Blakeout::with_params(32, 1024).unwrap();
+        digest.update([2, 0, 0, 0, 0, 0, 0, 0]);
+        digest.update(b"ab");
+        assert_eq!(digest.finalize(), hash(&[b"ab"], true));
+    }
+
+    #[test]
+    fn hash_stream() {
+        let chunks = [&DATA[..10], &DATA[10..11], &DATA[11..]];
+        let digests: Vec<[u8; 32]> = crate::hash_stream(chunks.iter()).collect();
+        assert_eq!(3, digests.len());
+        assert_eq!(Blakeout::digest(chunks[0]), digests[0]);
+        let mut chained = Blakeout::new();
+        for chunk in &chunks {
+            chained.chain(chunk);
+        }
+        assert_eq!(chained.finalize(), digests[2]);
+        assert_eq!(Blakeout::digest([&digests[0][..], chunks[1]].concat()), digests[1]);
+        assert_eq!(0, crate::hash_stream(Vec::<Vec<u8>>::new()).count());
+    }
+
+    #[test]
+    fn into_result_vec() {
+        let mut digest = Blakeout::new();
         digest.update(DATA);
-        let hash2 = digest.result_str();
+        digest.compute();
+        let result = digest.result().to_vec();
+        assert_eq!(result, digest.into_result_vec());
+        assert!(Blakeout::new().into_result_vec().is_empty());
+    }
 
-        assert_eq!(hash1, hash2);
+    #[test]
+    fn set_digest() {
+        let digest = crate::set_digest(&["one", "two", "three"]);
+        assert_eq!(digest, crate::set_digest(&["three", "one", "two"]));
+        assert_ne!(digest, crate::set_digest(&["one", "two", "four"]));
+        assert_ne!(digest, crate::set_digest(&["one", "two", "three", "three"]));
+        let mut digests = hash_many(&["one", "two", "three"]);
+        digests.sort();
+        assert_eq!(Blakeout::digest(digests.concat()), digest);
+    }
+
+    #[test]
+    fn chain() {
+        let mut chained = Blakeout::with_params(32, 1024).unwrap();
+        let mut iterated = chained.clone();
+        let mut single = chained.clone();
+        chained.chain(DATA);
+        single.update(DATA);
+        single.compute();
+        assert_eq!(single.result(), chained.result());
+
+        // Every round hashes the previous result followed by the data
+        iterated.update(DATA);
+        iterated.compute();
+        for _ in 0..3 {
+            chained.chain(DATA);
+            let previous = iterated.result().to_vec();
+            iterated.reset();
+            iterated.update(&previous);
+            iterated.update(DATA);
+            iterated.compute();
+        }
+        assert_eq!(iterated.result(), chained.result());
+        assert_ne!(single.result(), chained.result());
+
+        // Plain updates never fold previous results in
+        single.update(DATA);
+        single.compute();
+        let mut twice = Blakeout::with_params(32, 1024).unwrap();
+        twice.update([&DATA[..], &DATA[..]].concat());
+        twice.compute();
+        assert_eq!(twice.result(), single.result());
+    }
+
+    #[test]
+    fn combine() {
+        let mut a = Blakeout::with_params(32, 1024).unwrap();
+        a.update(b"first file");
+        a.compute();
+        let mut b = Blakeout::with_params(32, 1024).unwrap();
+        b.update(b"second file");
+        b.compute();
+
+        let mut ab = a.clone();
+        ab.combine(&b);
+        let mut ba = b.clone();
+        ba.combine(&a);
+        assert_ne!(ab, ba);
+        let mut expected = Blakeout::with_params(32, 1024).unwrap();
+        expected.update([a.result(), b.result()].concat());
+        expected.compute();
+        assert_eq!(expected, ab);
+
+        let mut ab = a.clone();
+        ab.combine_commutative(&b);
+        let mut ba = b.clone();
+        ba.combine_commutative(&a);
+        assert_eq!(ab, ba);
+        assert_ne!(a, ab);
+        assert_ne!(b, ab);
+
+        // The result stays readable after `finalize_reset` and is combined as is
+        let mut c = Blakeout::with_params(32, 1024).unwrap();
+        c.update(b"first file");
+        let c_result = c.finalize_reset();
+        c.combine(&b);
+        let mut expected = Blakeout::with_params(32, 1024).unwrap();
+        expected.update([&c_result[..], b.result()].concat());
+        expected.compute();
+        assert_eq!(expected, c);
+
+        // A pending message is hashed before combining
+        let mut pending = Blakeout::with_params(32, 1024).unwrap();
+        pending.update(b"first file");
+        pending.combine(&b);
+        assert_eq!(expected, pending);
+        let mut pending = Blakeout::with_params(32, 1024).unwrap();
+        pending.update(b"second file");
+        pending.combine_commutative(&a);
+        assert_eq!(ab, pending);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut digest = Blakeout::default();
+        digest.update(DATA);
+        digest.compute();
+        let hash1 = digest.result_str();
+        digest.reset();
+        digest.update(DATA);
+        digest.compute();
+        let hash2 = digest.result_str();
+
+        assert_eq!(hash1, hash2);
+    }
+
+    #[test]
+    fn generic_digest() {
+        let result = process::<Blakeout, _>(&mut Cursor::new(&DATA[..]));
+        assert_eq!("4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62", to_hex(&result));
+    }
+
+    #[test]
+    fn finalize() {
+        let mut digest = Blakeout::default();
+        digest.update(DATA);
+        digest.compute();
+        let expected = digest.result().to_vec();
+        assert_eq!(&expected[..], &digest.finalize_reset()[..]);
+        digest.update(DATA);
+        assert_eq!(&expected[..], &digest.finalize()[..]);
+    }
+
+    #[test]
+    fn custom_params() {
+        let mut digest = Blakeout::with_params(16, 1024).unwrap();
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!(16, digest.result().len());
+        let mut other = Blakeout::with_params(16, 1024).unwrap();
+        other.update(DATA);
+        other.compute();
+        assert_eq!(digest.result_str(), other.result_str());
+        assert_eq!("aaed56d401021ba165a1e2d1ef91b29a", digest.result_str());
+    }
+
+    #[test]
+    fn invalid_params() {
+        assert_eq!(Some(Error::InvalidHashSize(0)), Blakeout::with_params(0, 1024).err());
+        assert_eq!(Some(Error::InvalidHashSize(33)), Blakeout::with_params(33, 1024).err());
+        assert_eq!(Some(Error::InvalidHashCount(1)), Blakeout::with_params(32, 1).err());
+    }
+
+    #[test]
+    fn lazy_allocation() {
+        for _ in 0..1000 {
+            let digest = Blakeout::default();
+            assert_eq!(0, digest.buffer.capacity());
+        }
+        // Updates only append to the message, the scratchpad is needed by hashing
+        let mut digest = Blakeout::default();
+        digest.update(DATA);
+        assert_eq!(0, digest.buffer.capacity());
+        assert!(digest.result().is_empty());
+        digest.compute();
+        assert_eq!(32 * 65536, digest.buffer.len());
+        let capacity = digest.buffer.capacity();
+        digest.reset();
+        assert_eq!(capacity, digest.buffer.capacity());
+    }
+
+    #[test]
+    fn invalid_size_error() {
+        let mut digest: Blakeout = Blakeout::build(0, 16);
+        digest.update(DATA);
+        assert_eq!(Err(Error::InvalidOutputSize), digest.process_input(false));
+        assert_eq!(Err(Error::NotHashed), digest.try_result());
+
+        let mut digest = Blakeout::default();
+        digest.try_update(DATA).unwrap();
+        assert_eq!(Err(Error::NotHashed), digest.try_result());
+        digest.compute();
+        assert_eq!(Ok(digest.result()), digest.try_result());
+
+        let mut digest: Blakeout = Blakeout::build(32, usize::MAX / 16);
+        digest.update(DATA);
+        assert_eq!(Err(Error::BufferTooLarge), digest.process_input(false));
+        assert!(digest.buffer.is_empty());
+    }
+
+    #[test]
+    fn clone_state() {
+        let mut first = Blakeout::default();
+        first.update(DATA);
+        first.compute();
+        let mut second = first.clone();
+        first.update(b"first");
+        first.compute();
+        second.update(b"second");
+        second.compute();
+        assert_ne!(first.result(), second.result());
+
+        let mut fresh = Blakeout::default();
+        fresh.update(DATA);
+        fresh.update(b"first");
+        fresh.compute();
+        assert_eq!(fresh.result(), first.result());
+        fresh.reset();
+        fresh.update(DATA);
+        fresh.update(b"second");
+        fresh.compute();
+        assert_eq!(fresh.result(), second.result());
+    }
+
+    #[test]
+    fn matches_reference() {
+        for &(hash_size, hash_count) in &[(32, 65536), (32, 2), (16, 1000), (1, 77)] {
+            let mut digest = Blakeout::with_params(hash_size, hash_count).unwrap();
+            digest.update(DATA);
+            digest.compute();
+            assert_eq!(reference(DATA, hash_size, hash_count), digest.result());
+        }
+    }
+
+    /// Streaming by random chunks and one-shot hashing of random messages must agree
+    #[test]
+    fn streaming_matches_one_shot() {
+        let mut rng = XorShift(0x9e3779b97f4a7c15);
+        let mut next = move || rng.next_u64();
+        for _ in 0..20 {
+            let message: Vec<u8> = (0..next() % 300).map(|_| next() as u8).collect();
+            let mut streaming = Blakeout::with_params(32, 64).unwrap();
+            let mut rest = &message[..];
+            while !rest.is_empty() {
+                let (chunk, tail) = rest.split_at((next() as usize % 50 + 1).min(rest.len()));
+                streaming.update(chunk);
+                rest = tail;
+            }
+            let mut one_shot = Blakeout::with_params(32, 64).unwrap();
+            one_shot.update(&message);
+            one_shot.compute();
+            assert_eq!(reference(&message, 32, 64), one_shot.result());
+            assert_eq!(one_shot.finalize(), streaming.finalize());
+        }
+    }
+
+    #[test]
+    fn many_inputs() {
+        let inputs: Vec<Vec<u8>> = (0..8u8).map(|x| vec![x; x as usize * 10]).collect();
+        let results = hash_many(&inputs);
+        assert_eq!(inputs.len(), results.len());
+        for (data, result) in inputs.iter().zip(results.iter()) {
+            let mut digest = Blakeout::default();
+            digest.update(data);
+            digest.compute();
+            assert_eq!(digest.result(), &result[..]);
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "std"))]
+    fn no_std_hashing() {
+        let mut digest = Blakeout::default();
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!("4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62", digest.result_str());
+    }
+
+    #[test]
+    fn update_all() {
+        let mut digest = Blakeout::default();
+        digest.update_all([&DATA[..5], &DATA[5..20], &DATA[20..]]);
+        digest.compute();
+        assert_eq!(&Blakeout::digest(DATA)[..], digest.result());
+
+        let mut digest = Blakeout::default();
+        digest.update(&DATA[..5]);
+        digest.update_all(vec![DATA[5..].to_vec()]);
+        digest.compute();
+        assert_eq!(&Blakeout::digest(DATA)[..], digest.result());
+
+        let mut empty = Blakeout::default();
+        empty.update_all(Vec::<&[u8]>::new());
+        empty.compute();
+        assert_eq!(&Blakeout::digest(b"")[..], empty.result());
+    }
+
+    #[test]
+    fn empty_message() {
+        const EMPTY: &str = "1c997c631625c9b5f15469fc96785f56e57fbfa49e9a09b692f19b41e9722728";
+        let mut digest = Blakeout::default();
+        assert!(digest.result().is_empty());
+        assert_eq!("", digest.result_str());
+        assert_eq!(Err(Error::NotHashed), digest.try_result());
+        assert_eq!(None, digest.try_result().ok());
+        digest.update(b"");
+        digest.compute();
+        assert_eq!(Some(digest.result()), digest.try_result().ok());
+        assert_eq!(EMPTY, digest.result_str());
+        assert_eq!(EMPTY, to_hex(&Blakeout::new().finalize()));
+        assert_eq!(EMPTY, Blakeout::digest_hex([]));
+        assert_eq!(reference(b"", 32, 65536), digest.result());
+    }
+
+    #[test]
+    fn one_shot() {
+        assert_eq!("4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62", to_hex(&Blakeout::digest(DATA)));
+        assert_eq!("4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62", Blakeout::digest_hex(DATA));
+        assert_eq!("4BE892DAFF5D5432B43BF05C9D2EA4769DAF2DD1EC482C23839CE5D6950E9E62", Blakeout::digest_hex_upper(DATA));
+        assert_eq!(Blakeout::digest_hex(DATA), Blakeout::digest_fmt(DATA, HexCase::Lower));
+        assert_eq!(Blakeout::digest_hex_upper(DATA), Blakeout::digest_fmt(DATA, HexCase::Upper));
+        assert_eq!(Blakeout::digest_hex(DATA), Blakeout::digest_fmt(DATA, HexCase::default()));
+    }
+
+    #[test]
+    fn verify() {
+        let mut digest = Blakeout::default();
+        assert!(!digest.verify(&[]));
+        digest.update(DATA);
+        digest.compute();
+        let mut expected = Blakeout::digest(DATA);
+        assert!(digest.verify(&expected));
+        expected[31] ^= 1;
+        assert!(!digest.verify(&expected));
+        assert!(!digest.verify(&expected[..16]));
+        assert!(!digest.verify(&[expected, expected].concat()));
+
+        // The result of the previous message doesn't verify the pending one
+        expected[31] ^= 1;
+        digest.reset();
+        assert!(!digest.verify(&expected));
+        digest.update(b"other message");
+        assert!(!digest.verify(&expected));
+        assert_eq!(Ok(false), digest.verify_hex(&to_hex(&expected)));
+        assert!(digest == expected);
+        digest.compute();
+        assert!(!digest.verify(&expected));
+        assert!(digest.verify(&Blakeout::digest(b"other message")));
+    }
+
+    #[test]
+    fn verify_hex() {
+        let mut digest = Blakeout::default();
+        digest.update(DATA);
+        digest.compute();
+        let hex = digest.result_str();
+        assert_eq!(Ok(true), digest.verify_hex(&hex));
+        assert_eq!(Ok(true), digest.verify_hex(&hex.to_uppercase()));
+        assert_eq!(Ok(false), digest.verify_hex(&hex[..62]));
+        assert_eq!(Ok(false), digest.verify_hex(&to_hex(&[0u8; 32])));
+        assert_eq!(Err(Error::InvalidHex('x')), digest.verify_hex(&alloc::format!("x{}", &hex[1..])));
+        assert_eq!(Err(Error::InvalidLength(63)), digest.verify_hex(&hex[..63]));
+    }
+
+    #[test]
+    fn eq_and_verify_agree() {
+        let mut digest = Blakeout::with_params(32, 1024).unwrap();
+        digest.update(DATA);
+        digest.compute();
+        let mut expected = digest.result().to_vec();
+        assert!(digest == expected[..]);
+        assert!(digest.verify(&expected));
+        for i in [0, 15, 31] {
+            expected[i] ^= 1;
+            assert!(digest != expected[..]);
+            assert!(!digest.verify(&expected));
+            expected[i] ^= 1;
+        }
+        assert_eq!(digest == expected[..16], digest.verify(&expected[..16]));
+    }
+
+    #[test]
+    fn new_for_count() {
+        let mut digest = Blakeout::new_for_count(1024).unwrap();
+        assert_eq!(32 * 1024, digest.memory_cost());
+        digest.update(DATA);
+        digest.compute();
+        let mut expected = Blakeout::with_params(32, 1024).unwrap();
+        expected.update(DATA);
+        expected.compute();
+        assert_eq!(expected.result(), digest.result());
+        assert_eq!(Some(Error::HashCountNotPowerOfTwo(1000)), Blakeout::new_for_count(1000).err());
+        assert_eq!(Some(Error::InvalidHashCount(1)), Blakeout::new_for_count(1).err());
+        assert_eq!(Some(Error::InvalidHashCount(0)), Blakeout::new_for_count(0).err());
+    }
+
+    #[test]
+    fn copy_result_into() {
+        let mut digest = Blakeout::default();
+        let mut out = [0xffu8; 40];
+        assert_eq!(0, digest.copy_result_into(&mut out));
+        assert_eq!([0xff; 40], out);
+
+        digest.update(DATA);
+        digest.compute();
+        let expected = Blakeout::digest(DATA);
+        let mut exact = [0u8; 32];
+        assert_eq!(32, digest.copy_result_into(&mut exact));
+        assert_eq!(&expected[..], &exact[..]);
+
+        assert_eq!(32, digest.copy_result_into(&mut out));
+        assert_eq!(&expected[..], &out[..32]);
+        assert_eq!([0xff; 8], out[32..]);
+
+        let mut short = [0u8; 16];
+        assert_eq!(16, digest.copy_result_into(&mut short));
+        assert_eq!(&expected[..16], &short[..]);
+    }
+
+    #[test]
+    fn display() {
+        let mut digest = Blakeout::default();
+        assert_eq!("", alloc::format!("{}", digest));
+        assert_eq!("Blakeout { dirty: false, result: \"\" }", alloc::format!("{:?}", digest));
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!(digest.result_str(), alloc::format!("{}", digest));
+        assert_eq!(alloc::format!("Blakeout {{ dirty: true, result: {:?} }}", digest.result_str()), alloc::format!("{:?}", digest));
+    }
+
+    #[test]
+    fn debug_keyed() {
+        let key = b"very secret key";
+        let mut digest = BlakeoutBuilder::new().hash_count(1024).key(key).build().unwrap();
+        digest.update(DATA);
+        digest.compute();
+        let debug = alloc::format!("{:?}", digest);
+        assert_eq!("Blakeout { keyed: true, result: <redacted until finalized> }", debug);
+        assert!(!debug.contains("secret"));
+        assert!(!debug.contains(&to_hex(key)));
+        assert!(!debug.contains(&digest.result_str()));
+        assert!(!debug.contains(&to_hex(&digest.buffer[..32])));
+    }
+
+    #[test]
+    fn equality() {
+        let mut first = Blakeout::default();
+        let mut second = Blakeout::default();
+        assert_eq!(first, second);
+        first.update(DATA);
+        first.compute();
+        assert_ne!(first, second);
+        second.update(DATA);
+        second.compute();
+        assert_eq!(first, second);
+        second.update(DATA);
+        second.compute();
+        assert_ne!(first, second);
+        second.reset();
+        second.update(DATA);
+        second.compute();
+        first.reset();
+        assert!(!first.dirty && second.dirty);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn equality_to_bytes() {
+        let mut digest = Blakeout::default();
+        digest.update(DATA);
+        digest.compute();
+        let expected = Blakeout::digest(DATA);
+        assert_eq!(digest, expected);
+        assert_eq!(digest, &expected[..]);
+        assert_eq!(digest, expected[..]);
+        assert_ne!(digest, &expected[1..]);
+        assert_ne!(Blakeout::default(), expected);
+    }
+
+    #[test]
+    fn upper_case() {
+        let mut digest = Blakeout::default();
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!("4BE892DAFF5D5432B43BF05C9D2EA4769DAF2DD1EC482C23839CE5D6950E9E62", digest.result_str_upper());
+    }
+
+    #[test]
+    #[cfg(feature = "base64")]
+    fn base64() {
+        use base64::Engine;
+        let mut digest = Blakeout::default();
+        assert_eq!("", digest.result_base64());
+        digest.update(DATA);
+        digest.compute();
+        let encoded = digest.result_base64();
+        assert_eq!("S+iS2v9dVDK0O/BcnS6kdp2vLdHsSCwjg5zl1pUOnmI=", encoded);
+        assert_eq!(digest.result(), &base64::engine::general_purpose::STANDARD.decode(encoded).unwrap()[..]);
+    }
+
+    #[test]
+    #[cfg(feature = "bs58")]
+    fn base58() {
+        let mut digest = Blakeout::default();
+        assert_eq!("", digest.result_base58());
+        digest.update(DATA);
+        digest.compute();
+        let encoded = digest.result_base58();
+        assert_eq!("67KGU1mu7iWq5g1AhrMrZ2YwQn2JeR5aYaQ6x7sSAbVB", encoded);
+        assert_eq!(digest.result(), &bs58::decode(encoded).into_vec().unwrap()[..]);
+    }
+
+    #[test]
+    fn dirty_state() {
+        let mut digest = Blakeout::default();
+        assert!(!digest.is_dirty());
+        digest.update(DATA);
+        assert!(!digest.is_dirty());
+        digest.compute();
+        assert!(digest.is_dirty());
+        digest.update(DATA);
+        assert!(!digest.is_dirty());
+        digest.reset();
+        assert!(!digest.is_dirty());
+        digest.update_chained(DATA);
+        assert!(digest.is_dirty());
+        digest.finalize_reset();
+        assert!(!digest.is_dirty());
+    }
+
+    #[test]
+    fn clear() {
+        let mut digest = Blakeout::default();
+        digest.update(DATA);
+        digest.compute();
+        digest.clear();
+        assert!(!digest.buffer.is_empty());
+        assert!(digest.buffer.iter().all(|x| *x == 0));
+        assert_eq!(Blakeout::default(), digest);
+        assert_eq!("", digest.result_str());
+        digest.update(b"other");
+        digest.compute();
+
+        let mut fresh = Blakeout::default();
+        fresh.update(b"other");
+        fresh.compute();
+        assert_eq!(fresh.result(), digest.result());
+    }
+
+    /// Prefetching must not change results, run with and without `prefetch` feature
+    #[test]
+    fn prefetch_regression() {
+        assert_eq!("4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62", Blakeout::digest_hex(DATA));
+        for &(hash_size, hash_count) in &[(32, 4096), (5, 300), (1, 2)] {
+            let mut digest = Blakeout::with_params(hash_size, hash_count).unwrap();
+            digest.update(DATA);
+            digest.compute();
+            assert_eq!(reference(DATA, hash_size, hash_count), digest.result());
+        }
+    }
+
+    #[test]
+    fn buffer_untouched() {
+        let mut digest = Blakeout::default();
+        digest.update(DATA);
+        digest.compute();
+        let mut first = VarBlake2s::new(32).unwrap();
+        first.update(DATA);
+        assert_eq!(&first.finalize_boxed()[..], &digest.buffer[..32]);
+        assert_eq!("4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62", digest.result_str());
+
+        // Odd sizes make the last reversed chunk partial
+        let mut digest = Blakeout::with_params(7, 333).unwrap();
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!(reference(DATA, 7, 333), digest.result());
+    }
+
+    #[test]
+    fn small_scratchpad() {
+        // Whole scratchpad is smaller than one reversed chunk
+        let mut digest = Blakeout::with_params(16, 4).unwrap();
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!("f6a676c70ad4e27e121bd15dca41f999", digest.result_str());
+        let scratchpad = digest.buffer.clone();
+        digest.update([]);
+        digest.compute();
+        assert_eq!(scratchpad, digest.buffer);
+        assert_eq!("f6a676c70ad4e27e121bd15dca41f999", digest.result_str());
+    }
+
+    #[test]
+    fn reset_with_params() {
+        let mut digest = Blakeout::with_params(32, 4096).unwrap();
+        digest.update(DATA);
+        digest.compute();
+        let capacity = digest.buffer.capacity();
+        let pointer = digest.buffer.as_ptr();
+        digest.reset_with_params(16, 1024).unwrap();
+        assert!(!digest.is_dirty());
+        assert!(digest.result().is_empty());
+        assert_eq!(16, digest.output_len());
+        assert_eq!(16 * 1024, digest.memory_cost());
+        assert_eq!(capacity, digest.buffer.capacity());
+        assert_eq!(pointer, digest.buffer.as_ptr());
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!("aaed56d401021ba165a1e2d1ef91b29a", digest.result_str());
+
+        digest.reset_with_params(32, 65536).unwrap();
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!("4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62", digest.result_str());
+
+        assert_eq!(Err(Error::InvalidHashSize(33)), digest.reset_with_params(33, 1024));
+        assert_eq!(Err(Error::InvalidHashCount(1)), digest.reset_with_params(32, 1));
+        assert_eq!(32 * 65536, digest.memory_cost());
+        assert!(digest.is_dirty());
+    }
+
+    #[test]
+    fn result_generic() {
+        let mut digest = Blakeout::new();
+        assert_eq!(&[0u8; 32][..], &digest.result_generic()[..]);
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!(digest.result(), &digest.result_generic()[..]);
+        assert_eq!(digest.result_generic(), Digest::finalize(digest));
+    }
+
+    #[test]
+    fn dyn_update() {
+        fn feed(hasher: &mut dyn digest::Update, fields: &[&[u8]]) {
+            for field in fields {
+                hasher.update(field);
+            }
+        }
+        let mut digest = Blakeout::with_params(32, 1024).unwrap();
+        feed(&mut digest as &mut dyn digest::Update, &[&DATA[..10], &DATA[10..]]);
+        let mut digest2b = BlakeoutBuilder::new().hash_count(1024).build_inner::<VarBlake2b>().unwrap();
+        feed(&mut digest2b, &[DATA]);
+        digest.compute();
+        digest2b.compute();
+
+        let mut expected = Blakeout::with_params(32, 1024).unwrap();
+        expected.update(DATA);
+        expected.compute();
+        assert_eq!(expected.result(), digest.result());
+        let mut expected = BlakeoutBuilder::new().hash_count(1024).build_inner::<VarBlake2b>().unwrap();
+        expected.update(DATA);
+        expected.compute();
+        assert_eq!(expected.result(), digest2b.result());
+    }
+
+    proptest::proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(64))]
+
+        #[test]
+        fn reset_is_fresh(a in proptest::collection::vec(proptest::num::u8::ANY, 0..200), b in proptest::collection::vec(proptest::num::u8::ANY, 0..200)) {
+            let fresh = |data: &[u8]| {
+                let mut digest = Blakeout::with_params(32, 64).unwrap();
+                digest.update(data);
+                digest.finalize()
+            };
+            let mut digest = Blakeout::with_params(32, 64).unwrap();
+            digest.update(&a);
+            digest.reset();
+            digest.update(&a);
+            digest.compute();
+            proptest::prop_assert_eq!(fresh(&a), digest.result());
+            digest.reset();
+            digest.update(&b);
+            digest.compute();
+            proptest::prop_assert_eq!(fresh(&b), digest.result());
+            digest.reset();
+            proptest::prop_assert_eq!(fresh(&[]), digest.finalize());
+        }
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn blake3() {
+        let hash = |data: &[u8], size: usize| {
+            let mut out = vec![0u8; size];
+            blake3::Hasher::new().update(data).finalize_xof().fill(&mut out);
+            out
+        };
+        let reference = |data: &[u8], hash_size: usize, hash_count: usize| {
+            let mut buffer = hash(data, hash_size);
+            for x in 1..hash_count {
+                let next = hash(&buffer[x.saturating_sub(2) * hash_size..x * hash_size], hash_size);
+                buffer.extend_from_slice(&next);
+            }
+            let mut whole = buffer.clone();
+            whole.extend(buffer.iter().rev());
+            hash(&whole, hash_size)
+        };
+        let mut digest = crate::Blakeout3::new_inner();
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!(reference(DATA, 32, 65536), digest.result());
+        assert_eq!("ad85e9f4df745f70567cf7a5ce3f8360e733cc4f15d6dfef01251ed8c7f8d4fa", digest.result_str());
+        let mut digest = BlakeoutBuilder::new().hash_size(16).hash_count(1024).build_inner::<blake3::Hasher>().unwrap();
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!(reference(DATA, 16, 1024), digest.result());
+
+        let mut keyed = BlakeoutBuilder::new().key(b"key").build_inner::<blake3::Hasher>().unwrap();
+        keyed.update(DATA);
+        keyed.compute();
+        assert_ne!(reference(DATA, 32, 65536), keyed.result());
+        assert_eq!(Some(Error::InvalidSaltLength(1)), BlakeoutBuilder::new().salt(b"s").build_inner::<blake3::Hasher>().err());
+    }
+
+    #[test]
+    fn write_hex_to() {
+        let mut digest = Blakeout::with_params(32, 1024).unwrap();
+        let mut buffer = alloc::string::String::with_capacity(64);
+        digest.write_hex_to(&mut buffer).unwrap();
+        assert!(buffer.is_empty());
+        digest.update(DATA);
+        digest.compute();
+        digest.write_hex_to(&mut buffer).unwrap();
+        assert_eq!(digest.result_str(), buffer);
+
+        let capacity = buffer.capacity();
+        buffer.clear();
+        digest.update(DATA);
+        digest.compute();
+        digest.write_hex_to(&mut buffer).unwrap();
+        assert_eq!(digest.result_str(), buffer);
+        assert_eq!(capacity, buffer.capacity());
+    }
+
+    #[test]
+    fn large_input() {
+        let data: Vec<u8> = (0..1_000_000u32).map(|x| (x % 253) as u8).collect();
+        let mut digest = Blakeout::with_params(32, 1024).unwrap();
+        digest.update_all(data.chunks(1000));
+        digest.compute();
+        assert!(digest.input.capacity() <= 2 * data.len());
+        let mut expected = Blakeout::with_params(32, 1024).unwrap();
+        expected.update(&data);
+        expected.compute();
+        assert_eq!(expected.result(), digest.result());
+
+        let mut limited = BlakeoutBuilder::new().hash_count(1024).max_input(data.len()).build().unwrap();
+        limited.update(&data[..500_000]);
+        assert_eq!(Err(Error::InputTooLarge), limited.try_update(&data));
+        assert_eq!(500_000, limited.input.len());
+        assert!(limited.input.capacity() < 2 * data.len());
+        limited.update(&data[500_000..]);
+        limited.compute();
+        assert_eq!(expected.result(), limited.result());
+        assert_eq!(Err(Error::InputTooLarge), limited.try_update([0]));
+        assert_eq!(Ok(()), limited.try_update([]));
+    }
+
+    #[test]
+    fn try_updates() {
+        let limited = || BlakeoutBuilder::new().hash_count(1024).max_input(4).build().unwrap();
+        let mut digest = limited();
+        digest.update(b"ab");
+        assert_eq!(Err(Error::InputTooLarge), digest.try_update_all([&b"c"[..], b"de"]));
+        assert_eq!(Err(Error::InputTooLarge), digest.try_update_framed(b""));
+        assert_eq!(Err(Error::InputTooLarge), digest.try_update_str_ci("CDE"));
+        assert_eq!(Err(Error::InputTooLarge), digest.try_chain(b"abcde"));
+        assert_eq!(b"ab", &digest.input[..]);
+        assert!(digest.result().is_empty());
+        assert_eq!(Ok(()), digest.try_update_str_ci("CD"));
+        let mut expected = limited();
+        expected.update(b"abcd");
+        assert_eq!(expected.finalize(), digest.finalize());
+    }
+
+    #[test]
+    #[should_panic(expected = "message is longer than max_input")]
+    fn update_too_large() {
+        let mut digest = BlakeoutBuilder::new().max_input(4).build().unwrap();
+        digest.update(b"abcde");
+    }
+
+    #[test]
+    fn memory_cost() {
+        assert_eq!(32 * 65536, Blakeout::default_memory_cost());
+        assert_eq!(Blakeout::default_memory_cost(), Blakeout::new().memory_cost());
+        let mut digest = Blakeout::with_params(16, 1024).unwrap();
+        assert_eq!(16 * 1024, digest.memory_cost());
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!(digest.buffer.len(), digest.memory_cost());
+        assert_eq!(64 * 65536, Blakeout2b::new_inner().memory_cost());
+    }
+
+    #[test]
+    fn presets() {
+        assert_eq!(64 * 1024, Blakeout::preset_interactive().memory_cost());
+        assert_eq!(2 * 1024 * 1024, Blakeout::preset_sensitive().memory_cost());
+        assert_eq!(16 * 1024 * 1024, Blakeout::preset_pow().memory_cost());
+        let mut digest = Blakeout::preset_sensitive();
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!(&Blakeout::digest(DATA)[..], digest.result());
+        let mut digest = Blakeout::preset_interactive();
+        digest.update(DATA);
+        digest.compute();
+        let mut expected = Blakeout::with_params(32, 2048).unwrap();
+        expected.update(DATA);
+        expected.compute();
+        assert_eq!(expected.result(), digest.result());
+    }
+
+    #[test]
+    fn as_ref() {
+        fn hex<T: AsRef<[u8]>>(bytes: T) -> alloc::string::String {
+            to_hex(bytes.as_ref())
+        }
+        let mut digest = Blakeout::default();
+        assert_eq!("", hex(&digest));
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!(digest.result_str(), hex(&digest));
+    }
+
+    #[test]
+    fn result_size() {
+        let mut digest = Blakeout::with_params(16, 1024).unwrap();
+        assert_eq!(16, digest.output_len());
+        assert_eq!(32, Blakeout::output_size());
+        const SIZE: usize = Blakeout::output_size();
+        let out = [0u8; Blakeout::DEFAULT_OUTPUT_SIZE];
+        let _: [u8; SIZE] = out;
+        assert_eq!(Blakeout::digest(DATA).len(), out.len());
+        assert_eq!(64, Blakeout2b::new_inner().output_len());
+        assert!(digest.result().is_empty());
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!("aaed56d401021ba165a1e2d1ef91b29a", digest.result_str());
+        digest.reset();
+        assert_eq!(16, digest.result().len());
+        assert_eq!(&[0u8; 16], &digest.clone().finalize()[16..]);
+        digest.clear();
+        assert!(digest.result().is_empty());
+    }
+
+    #[test]
+    fn xof() {
+        let mut digest = Blakeout::default();
+        digest.update(DATA);
+        let mut long = [0u8; 100];
+        digest.finalize_xof(&mut long);
+        let mut short = [0u8; 32];
+        digest.finalize_xof(&mut short);
+        assert_eq!(&long[..32], &short[..]);
+        assert_ne!(digest.result(), &short[..]);
+        let mut other = Blakeout::default();
+        other.update(DATA);
+        let mut again = [0u8; 100];
+        other.finalize_xof(&mut again);
+        assert_eq!(&long[..], &again[..]);
+        assert_ne!(&long[..32], &long[32..64]);
+        assert_eq!("2301c79070a0a68125733b247a25e82575b95eb2a73a49ce0fbf25cce000fcc3", to_hex(&long[..32]));
+    }
+
+    #[test]
+    fn keyed() {
+        let mut digest = Blakeout::new_keyed(b"first key").unwrap();
+        digest.update(DATA);
+        digest.compute();
+        let mut other = Blakeout::new_keyed(b"second key").unwrap();
+        other.update(DATA);
+        other.compute();
+        assert_eq!("7e7ae14f89104fd5404b25b9319ad0c4840f901f031ca686e00e71135ed11d12", digest.result_str());
+        assert_ne!(digest.result(), other.result());
+
+        let mut empty = Blakeout::new_keyed(&[]).unwrap();
+        empty.update(DATA);
+        empty.compute();
+        assert_eq!(&Blakeout::digest(DATA)[..], empty.result());
+
+        assert_eq!(Some(Error::InvalidKeyLength(33)), Blakeout::new_keyed(&[0u8; 33]).err());
+    }
+
+    #[test]
+    fn hmac() {
+        let mut mac = SimpleHmac::<Blakeout>::new_from_slice(b"secret key").unwrap();
+        mac.update(DATA);
+        let tag = mac.finalize().into_bytes();
+        assert_eq!("29707973c1c1ec5758f12e16cf548fec76dad6005f692f38b6569b9365e499de", to_hex(&tag));
+    }
+
+    #[test]
+    fn salt() {
+        let mut first = Blakeout::new_with_salt(b"salt one", b"personal");
+        first.update(DATA);
+        first.compute();
+        let mut second = Blakeout::new_with_salt(b"salt two", b"personal");
+        second.update(DATA);
+        second.compute();
+        assert_ne!(first, second);
+        let mut built = BlakeoutBuilder::new().salt(b"salt one").personal(b"personal").build().unwrap();
+        built.update(DATA);
+        built.compute();
+        assert_eq!(built, first);
+
+        let mut zero = Blakeout::new_with_salt(&[0u8; 8], &[0u8; 8]);
+        zero.update(DATA);
+        zero.compute();
+        assert_eq!("4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62", zero.result_str());
+    }
+
+    #[test]
+    fn domain() {
+        let mut first = Blakeout::new_with_domain(b"first");
+        first.update(DATA);
+        first.compute();
+        let mut second = Blakeout::new_with_domain(b"second");
+        second.update(DATA);
+        second.compute();
+        assert_ne!(first.result(), second.result());
+        assert_ne!(&Blakeout::digest(DATA)[..], first.result());
+
+        // The length prefix makes the domain differ from a prefix of the message
+        let mut prefixed = Blakeout::new();
+        prefixed.update(b"first");
+        prefixed.update(DATA);
+        prefixed.compute();
+        assert_ne!(prefixed.result(), first.result());
+
+        let mut empty = Blakeout::new_with_domain(b"");
+        empty.update(DATA);
+        empty.compute();
+        assert_eq!(&Blakeout::digest(DATA)[..], empty.result());
+    }
+
+    #[test]
+    fn blake2b() {
+        let mut digest = Blakeout2b::new_inner();
+        digest.update(DATA);
+        digest.compute();
+        assert_eq!(64, digest.result().len());
+        assert_eq!("a5e97279599c72ebc519422b705b86917f4d21953175b57e1effb3e4643fa2d6d9c941493b9b2c6bfb7463a0800860437e23e66b552d81df1351eb5164a695b6", digest.result_str());
+
+        let mut short = BlakeoutBuilder::new().hash_size(32).hash_count(1024).build_inner::<VarBlake2b>().unwrap();
+        short.update(DATA);
+        short.compute();
+        assert_eq!("789defdeee07a41628e8dc63ff89712ac927253f9a91738bec0d041475ae6a38", short.result_str());
+
+        assert_eq!(Some(Error::InvalidHashSize(65)), BlakeoutBuilder::new().hash_size(65).build_inner::<VarBlake2b>().err());
+        assert_eq!(Some(Error::InvalidHashSize(33)), BlakeoutBuilder::new().hash_size(33).build().err());
+        assert!(BlakeoutBuilder::new().key(&[0u8; 64]).salt(&[0u8; 16]).build_inner::<VarBlake2b>().is_ok());
     }
 }
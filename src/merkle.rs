@@ -0,0 +1,77 @@
+use alloc::vec::Vec;
+
+use crate::{Blakeout, DEFAULT_HASH_SIZE};
+
+/// Prefix of hashed leaves
+const LEAF_PREFIX: u8 = 0;
+/// Prefix of hashed pairs of nodes
+const NODE_PREFIX: u8 = 1;
+
+/// Computes Merkle tree root of `leaves` using Blakeout with default parameters.
+///
+/// Every leaf is hashed as `Blakeout(0x00 || leaf)`, then every pair of nodes on each level
+/// is hashed as `Blakeout(0x01 || left || right)` until one node is left, it is the root.
+/// If a level has odd number of nodes, the last one is paired with itself.
+/// The prefixes make leaves and nodes never collide. Root of one leaf is the hash of the leaf,
+/// root of no leaves is `Blakeout::digest` of an empty message.
+///
+/// Every leaf and node costs one memory hard hashing, so `n` leaves take about `2 * n` of them.
+pub fn merkle_root<T: AsRef<[u8]>>(leaves: &[T]) -> [u8; DEFAULT_HASH_SIZE] {
+    if leaves.is_empty() {
+        return Blakeout::digest([]);
+    }
+    let mut hasher = Blakeout::new();
+    let mut level: Vec<[u8; DEFAULT_HASH_SIZE]> = leaves
+        .iter()
+        .map(|leaf| {
+            hasher.update_all([&[LEAF_PREFIX][..], leaf.as_ref()]);
+            hasher.finalize_reset()
+        })
+        .collect();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| {
+                let right = pair.get(1).unwrap_or(&pair[0]);
+                hasher.update_all([&[NODE_PREFIX][..], &pair[0], right]);
+                hasher.finalize_reset()
+            })
+            .collect();
+    }
+    level[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use crate::{Blakeout, merkle_root};
+
+    fn leaf(data: &[u8]) -> [u8; 32] {
+        Blakeout::digest([&[0u8], data].concat())
+    }
+
+    fn node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        Blakeout::digest([&[1u8][..], left, right].concat())
+    }
+
+    #[test]
+    fn trees() {
+        let a = leaf(b"a");
+        let b = leaf(b"b");
+        let c = leaf(b"c");
+        let d = leaf(b"d");
+        assert_eq!(a, merkle_root(&[b"a"]));
+        assert_eq!(node(&a, &b), merkle_root(&[b"a", b"b"]));
+        assert_eq!(node(&node(&a, &b), &node(&c, &c)), merkle_root(&[b"a", b"b", b"c"]));
+        assert_eq!(node(&node(&a, &b), &node(&c, &d)), merkle_root(&[b"a", b"b", b"c", b"d"]));
+    }
+
+    #[test]
+    fn empty_and_order() {
+        let empty: &[Vec<u8>] = &[];
+        assert_eq!(Blakeout::digest([]), merkle_root(empty));
+        assert_ne!(merkle_root(&[b"a", b"b"]), merkle_root(&[b"b", b"a"]));
+        // Prefix makes a leaf differ from the plain hash
+        assert_ne!(Blakeout::digest(b"a"), merkle_root(&[b"a"]));
+    }
+}
@@ -0,0 +1,51 @@
+use std::io;
+use std::panic;
+use std::path::Path;
+use std::vec::Vec;
+
+use crate::{Blakeout, DEFAULT_HASH_SIZE};
+
+/// Hashes `data` with default parameters on the blocking thread pool of Tokio runtime
+/// and returns the result, the same as `Blakeout::digest(data)`.
+///
+/// One hash takes milliseconds of CPU time, so it is offloaded by `tokio::task::spawn_blocking`
+/// to keep the runtime workers free. Must be called within a Tokio runtime.
+/// Panics if hashing panics or the runtime is shutting down.
+pub async fn hash_async(data: Vec<u8>) -> [u8; DEFAULT_HASH_SIZE] {
+    let task = tokio::task::spawn_blocking(move || Blakeout::digest(data));
+    match task.await {
+        Ok(result) => result,
+        Err(e) => panic::resume_unwind(e.into_panic()),
+    }
+}
+
+/// Reads the file at `path` by `tokio::fs` and hashes its contents by `hash_async`.
+///
+/// The whole file is read to memory, as Blakeout keeps the whole message anyway.
+pub async fn hash_file_async<P: AsRef<Path>>(path: P) -> io::Result<[u8; DEFAULT_HASH_SIZE]> {
+    let data = tokio::fs::read(path).await?;
+    Ok(hash_async(data).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use crate::{Blakeout, hash_async, hash_file_async};
+
+    #[tokio::test]
+    async fn hash() {
+        let data = b"Science cannot solve the ultimate mystery of nature.".to_vec();
+        assert_eq!(Blakeout::digest(&data), hash_async(data).await);
+        assert_eq!(Blakeout::digest([]), hash_async(vec![]).await);
+    }
+
+    #[tokio::test]
+    async fn file() {
+        let path = std::env::temp_dir().join(format!("blakeout-file-async-{}", std::process::id()));
+        fs::write(&path, b"Hello, world!").unwrap();
+        let result = hash_file_async(&path).await.unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(Blakeout::digest(b"Hello, world!"), result);
+        assert!(hash_file_async(&path).await.is_err());
+    }
+}
@@ -0,0 +1,260 @@
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+
+use crate::{Blakeout, DEFAULT_HASH_SIZE, Error, from_hex, to_hex};
+
+/// Result hash of default Blakeout as a value.
+///
+/// It can be parsed from exactly 64 HEX digits and compared to the result of `finalize`:
+///
+/// ```rust
+/// use std::convert::TryFrom;
+/// use blakeout::{Blakeout, Output};
+///
+/// let expected = Output::try_from("6cc4bddb52416711be65e4b0201106fda4ceb0de48dfdce7e3a136e490d8586f").unwrap();
+/// assert_eq!(expected, Blakeout::digest(b"hello world"));
+/// ```
+///
+/// With the `serde` feature it is serialized as a HEX string in human readable formats,
+/// like JSON, and as raw bytes in binary ones. It is named `Output` to not be confused
+/// with `digest::Digest` trait.
+///
+/// Outputs are ordered lexicographically by their bytes, so they can be sorted
+/// and used as keys of ordered and hashed collections. `Hash` feeds the 32 bytes
+/// to the collection's hasher, it doesn't run Blakeout again.
+///
+/// `==` is not in constant time, which is fine for public hashes, use `Blakeout::verify`
+/// to compare secret ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Output(pub [u8; DEFAULT_HASH_SIZE]);
+
+/// Wraps the bytes of a result hash as they are, without hashing them
+impl From<[u8; DEFAULT_HASH_SIZE]> for Output {
+    fn from(bytes: [u8; DEFAULT_HASH_SIZE]) -> Self {
+        Output(bytes)
+    }
+}
+
+/// Hashes the bytes with default parameters by `Blakeout::digest`, it runs the whole
+/// memory hard hashing and is not a cheap conversion, unlike `From<[u8; 32]>`
+impl From<&[u8]> for Output {
+    fn from(data: &[u8]) -> Self {
+        Output(Blakeout::digest(data))
+    }
+}
+
+/// Hashes the bytes with default parameters, the same as `From<&[u8]>`
+impl From<Vec<u8>> for Output {
+    fn from(data: Vec<u8>) -> Self {
+        Output::from(&data[..])
+    }
+}
+
+impl AsRef<[u8]> for Output {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl IntoIterator for Output {
+    type Item = u8;
+    type IntoIter = core::array::IntoIter<u8, DEFAULT_HASH_SIZE>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self.0)
+    }
+}
+
+impl PartialEq<[u8; DEFAULT_HASH_SIZE]> for Output {
+    fn eq(&self, other: &[u8; DEFAULT_HASH_SIZE]) -> bool {
+        &self.0 == other
+    }
+}
+
+/// Parses exactly 64 HEX digits, in upper or lower case
+impl TryFrom<&str> for Output {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        if s.len() != DEFAULT_HASH_SIZE * 2 {
+            return Err(Error::InvalidLength(s.len()));
+        }
+        let mut output = [0u8; DEFAULT_HASH_SIZE];
+        output.copy_from_slice(&from_hex(s)?);
+        Ok(Output(output))
+    }
+}
+
+/// Writes the hash in lower case HEX format
+impl fmt::Display for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&to_hex(&self.0))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use core::fmt;
+
+    use serde::{Deserializer, Serializer, de};
+
+    use crate::{DEFAULT_HASH_SIZE, Output, from_hex, to_hex};
+
+    impl serde::Serialize for Output {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                serializer.serialize_str(&to_hex(&self.0))
+            } else {
+                serializer.serialize_bytes(&self.0)
+            }
+        }
+    }
+
+    impl<'de> serde::Deserialize<'de> for Output {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                deserializer.deserialize_str(OutputVisitor)
+            } else {
+                deserializer.deserialize_bytes(OutputVisitor)
+            }
+        }
+    }
+
+    struct OutputVisitor;
+
+    impl<'de> de::Visitor<'de> for OutputVisitor {
+        type Value = Output;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{} bytes or a HEX string of them", DEFAULT_HASH_SIZE)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            let bytes = from_hex(v).map_err(E::custom)?;
+            self.visit_bytes(&bytes)
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            let mut output = [0u8; DEFAULT_HASH_SIZE];
+            if v.len() != output.len() {
+                return Err(E::invalid_length(v.len(), &self));
+            }
+            output.copy_from_slice(v);
+            Ok(Output(output))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::TryFrom;
+    use alloc::format;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+    use crate::{Blakeout, Error, Output};
+    const DATA: &[u8; 29] = b"Science is poetry of reality!";
+    const HEX: &str = "4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62";
+    #[cfg(feature = "serde")]
+    const JSON: &str = "\"4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62\"";
+
+    #[test]
+    fn conversion() {
+        let output = Output::from(Blakeout::digest(DATA));
+        assert_eq!(&Blakeout::digest(DATA)[..], output.as_ref());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_set() {
+        use std::collections::HashSet;
+
+        let [a, b, c] = [&b"a"[..], b"b", b"c"].map(|data| Output::from(Blakeout::digest(data)));
+        let set: HashSet<Output> = [a, b, a, a, b].iter().copied().collect();
+        assert_eq!(2, set.len());
+        assert!(set.contains(&a));
+        assert!(set.contains(&b));
+        assert!(!set.contains(&c));
+    }
+
+    #[test]
+    fn from_data() {
+        assert_eq!(HEX, Output::from(&DATA[..]).to_string());
+        assert_eq!(HEX, Output::from(DATA.to_vec()).to_string());
+        let output: Output = DATA.as_ref().into();
+        assert_eq!(output, Blakeout::digest(DATA));
+        // Arrays are wrapped, not hashed
+        assert_eq!(Output::from(Blakeout::digest(DATA)), output);
+    }
+
+    #[test]
+    fn ordering() {
+        let mut outputs: Vec<Output> = [&b"a"[..], b"b", b"c", b"d", b"e"]
+            .iter()
+            .map(|data| {
+                let mut digest = Blakeout::with_params(32, 16).unwrap();
+                digest.update(data);
+                digest.compute();
+                digest.output()
+            })
+            .collect();
+        let mut bytes: Vec<[u8; 32]> = outputs.iter().map(|output| output.0).collect();
+        outputs.sort();
+        bytes.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+        assert_eq!(bytes, outputs.iter().map(|output| output.0).collect::<Vec<_>>());
+        for pair in outputs.windows(2) {
+            assert!(pair[0] < pair[1]);
+            let first = pair[0].0.iter().zip(pair[1].0.iter()).find(|(a, b)| a != b).unwrap();
+            assert!(first.0 < first.1);
+        }
+        outputs.dedup();
+        assert_eq!(5, outputs.len());
+    }
+
+    #[test]
+    fn iterator() {
+        let mut digest = Blakeout::new();
+        digest.update(DATA);
+        digest.compute();
+        let output = Output::from(digest.clone().finalize());
+        assert_eq!(digest.result(), &output.into_iter().collect::<Vec<u8>>()[..]);
+    }
+
+    #[test]
+    fn parse() {
+        let output = Output::try_from(HEX).unwrap();
+        assert_eq!(output, Blakeout::digest(DATA));
+        assert_eq!(HEX, output.to_string());
+        assert_eq!(Ok(output), Output::try_from(HEX.to_uppercase().as_str()));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(Err(Error::InvalidLength(62)), Output::try_from(&HEX[2..]));
+        assert_eq!(Err(Error::InvalidLength(66)), Output::try_from(format!("{}00", HEX).as_str()));
+        assert_eq!(Err(Error::InvalidLength(0)), Output::try_from(""));
+        let invalid = HEX.replace('e', "g");
+        assert_eq!(Err(Error::InvalidHex('g')), Output::try_from(invalid.as_str()));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn json() {
+        let output = Output::from(Blakeout::digest(DATA));
+        assert_eq!(JSON, serde_json::to_string(&output).unwrap());
+        assert_eq!(output, serde_json::from_str::<Output>(JSON).unwrap());
+        assert!(serde_json::from_str::<Output>("\"4be892\"").is_err());
+        assert!(serde_json::from_str::<Output>("\"not a hex\"").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn bincode() {
+        let output = Output::from(Blakeout::digest(DATA));
+        let bytes = bincode::serialize(&output).unwrap();
+        // Length prefix and raw bytes, no HEX
+        assert_eq!(8 + 32, bytes.len());
+        assert_eq!(&output.0[..], &bytes[8..]);
+        assert_eq!(output, bincode::deserialize::<Output>(&bytes).unwrap());
+    }
+}
@@ -0,0 +1,198 @@
+use alloc::vec::Vec;
+
+use crate::state::Reader;
+use crate::{Blakeout, BlakeoutBuilder, BlakeoutMode, Error, InnerDigest};
+
+/// Version of encoded parameters format, must be changed with any change of the format
+const VERSION: u8 = 4;
+
+/// Parameters of a hasher, they can be published alongside a digest to let others
+/// reconstruct a matching hasher by `builder`.
+///
+/// The key is never included, only the fact that the hasher is keyed. The inner hash
+/// function is recorded by `inner`, the builder must build a hasher with the same one.
+///
+/// ```rust
+/// use blakeout::{BlakeoutBuilder, Params};
+///
+/// let mut hasher = BlakeoutBuilder::new().hash_count(1024).salt(b"salt").build().unwrap();
+/// hasher.update(b"hello world");
+/// let encoded = hasher.params().encode();
+///
+/// let mut verifier = Params::decode(&encoded).unwrap().builder().build().unwrap();
+/// verifier.update(b"hello world");
+/// assert_eq!(hasher.finalize(), verifier.finalize());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Params {
+    /// Identifier of inner hash function, see `InnerDigest::ID` and `is_inner`
+    pub inner: u8,
+    /// Size of scratchpad hashes and the result in bytes
+    pub hash_size: usize,
+    /// Number of hashes in the scratchpad
+    pub hash_count: usize,
+    /// Number of previous hashes used to get every next one
+    pub lookback_blocks: usize,
+    /// Passes over the filled scratchpad
+    pub mode: BlakeoutMode,
+    /// Whether the hasher uses a key
+    pub keyed: bool,
+    /// Salt of inner hash function
+    pub salt: Vec<u8>,
+    /// Personalization of inner hash function
+    pub personal: Vec<u8>,
+    /// Domain hashed before the message
+    pub domain: Vec<u8>,
+}
+
+impl Params {
+    /// Encodes the parameters to a compact byte string, see `decode`
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(52 + self.salt.len() + self.personal.len() + self.domain.len());
+        bytes.push(VERSION);
+        bytes.push(self.inner);
+        bytes.push(self.keyed as u8);
+        bytes.push(self.mode as u8);
+        bytes.extend_from_slice(&(self.hash_size as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.hash_count as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.lookback_blocks as u64).to_le_bytes());
+        for field in &[&self.salt, &self.personal, &self.domain] {
+            bytes.extend_from_slice(&(field.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(field);
+        }
+        bytes
+    }
+
+    /// Decodes the parameters encoded by `encode`, returns `Error::InvalidParams` if they
+    /// are malformed. The values are checked only when a hasher is built.
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        Self::read(&mut Reader(bytes)).map_err(|_| Error::InvalidParams)
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, Error> {
+        if reader.byte()? != VERSION {
+            return Err(Error::InvalidParams);
+        }
+        let inner = reader.byte()?;
+        let keyed = match reader.byte()? {
+            0 => false,
+            1 => true,
+            _ => return Err(Error::InvalidParams),
+        };
+        let mode = match reader.byte()? {
+            0 => BlakeoutMode::DualPass,
+            1 => BlakeoutMode::ForwardOnly,
+            _ => return Err(Error::InvalidParams),
+        };
+        let hash_size = reader.size()?;
+        let hash_count = reader.size()?;
+        let lookback_blocks = reader.size()?;
+        let salt = reader.field()?.to_vec();
+        let personal = reader.field()?.to_vec();
+        let domain = reader.field()?.to_vec();
+        if !reader.0.is_empty() {
+            return Err(Error::InvalidParams);
+        }
+        Ok(Params { inner, hash_size, hash_count, lookback_blocks, mode, keyed, salt, personal, domain })
+    }
+
+    /// Returns `true` if the parameters are of a hasher with inner hash function `D`
+    pub fn is_inner<D: InnerDigest>(&self) -> bool {
+        self.inner == D::ID
+    }
+
+    /// Creates a builder with these parameters, if `keyed` is set
+    /// the key must be set by `BlakeoutBuilder::key`.
+    ///
+    /// The inner hash function is not set by the builder: use `build` only if
+    /// `is_inner::<VarBlake2s>()` holds, otherwise `build_inner` with the matching one.
+    pub fn builder(&self) -> BlakeoutBuilder {
+        BlakeoutBuilder::new()
+            .hash_size(self.hash_size)
+            .hash_count(self.hash_count)
+            .lookback_blocks(self.lookback_blocks)
+            .mode(self.mode)
+            .salt(&self.salt)
+            .personal(&self.personal)
+            .domain(&self.domain)
+    }
+}
+
+impl<D: InnerDigest> Blakeout<D> {
+    /// Returns the parameters of this hasher, without the key
+    pub fn params(&self) -> Params {
+        Params {
+            inner: D::ID,
+            hash_size: self.hash_size,
+            hash_count: self.hash_count,
+            lookback_blocks: self.lookback,
+            mode: self.mode,
+            keyed: !self.key.is_empty(),
+            salt: self.salt.clone(),
+            personal: self.personal.clone(),
+            domain: self.domain.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use crate::{Blakeout, Blakeout2b, BlakeoutBuilder, BlakeoutMode, Error, Params, VarBlake2b, VarBlake2s};
+    const DATA: &[u8; 29] = b"Science is poetry of reality!";
+
+    #[test]
+    fn round_trip() {
+        let params = Blakeout::new().params();
+        assert_eq!(Params { inner: 1, hash_size: 32, hash_count: 65536, lookback_blocks: 2, mode: BlakeoutMode::DualPass, keyed: false, salt: vec![], personal: vec![], domain: vec![] }, params);
+        assert_eq!(Ok(params.clone()), Params::decode(&params.encode()));
+
+        let digest = BlakeoutBuilder::new().hash_size(16).hash_count(1024).lookback_blocks(3).mode(BlakeoutMode::ForwardOnly).key(b"key").salt(b"salt").personal(b"person").domain(b"domain").build().unwrap();
+        let params = digest.params();
+        assert!(params.keyed);
+        assert!(!params.encode().windows(3).any(|w| w == b"key"));
+        assert_eq!(Ok(params.clone()), Params::decode(&params.encode()));
+    }
+
+    #[test]
+    fn inner() {
+        let params = Blakeout::new().params();
+        assert!(params.is_inner::<VarBlake2s>());
+        let params2b = Blakeout2b::new_inner().params();
+        assert_eq!(2, params2b.inner);
+        assert_ne!(params.encode(), params2b.encode());
+        let decoded = Params::decode(&params2b.encode()).unwrap();
+        assert!(decoded.is_inner::<VarBlake2b>() && !decoded.is_inner::<VarBlake2s>());
+        #[cfg(feature = "blake3")]
+        assert!(Params::decode(&crate::Blakeout3::new_inner().params().encode()).unwrap().is_inner::<blake3::Hasher>());
+    }
+
+    #[test]
+    fn reconstruct() {
+        let mut digest = BlakeoutBuilder::new().hash_count(1024).key(b"key").personal(b"person").domain(b"domain").build().unwrap();
+        digest.update(DATA);
+        digest.compute();
+        let params = Params::decode(&digest.params().encode()).unwrap();
+        let mut verifier = params.builder().key(b"key").build().unwrap();
+        verifier.update(DATA);
+        verifier.compute();
+        assert_eq!(digest.result(), verifier.result());
+    }
+
+    #[test]
+    fn invalid() {
+        let bytes = Blakeout::new().params().encode();
+        assert_eq!(Err(Error::InvalidParams), Params::decode(&bytes[..bytes.len() - 1]));
+        assert_eq!(Err(Error::InvalidParams), Params::decode(&[&bytes[..], &[0]].concat()));
+        assert_eq!(Err(Error::InvalidParams), Params::decode(&[]));
+        let mut version = bytes.clone();
+        version[0] += 1;
+        assert_eq!(Err(Error::InvalidParams), Params::decode(&version));
+        let mut keyed = bytes.clone();
+        keyed[2] = 2;
+        assert_eq!(Err(Error::InvalidParams), Params::decode(&keyed));
+        let mut mode = bytes;
+        mode[3] = 2;
+        assert_eq!(Err(Error::InvalidParams), Params::decode(&mode));
+    }
+}
@@ -0,0 +1,130 @@
+use std::cell::RefCell;
+use std::sync::Mutex;
+use std::vec::Vec;
+
+use crate::{Blakeout, DEFAULT_HASH_SIZE};
+
+/// A pool of reusable Blakeout hashers with default parameters, it can be shared between threads.
+///
+/// Every new hasher allocates a 2 MB scratchpad, the pool keeps hashers after use,
+/// so the allocation happens once per concurrently used hasher instead of once per hash.
+/// The pool grows to the largest number of simultaneous `hash` calls and never shrinks.
+///
+/// ```
+/// use std::sync::Arc;
+/// use blakeout::{Blakeout, BlakeoutPool};
+///
+/// let pool = Arc::new(BlakeoutPool::new());
+/// let handle = {
+///     let pool = Arc::clone(&pool);
+///     std::thread::spawn(move || pool.hash(b"Hello, world!"))
+/// };
+/// assert_eq!(Blakeout::digest(b"Hello, world!"), handle.join().unwrap());
+/// ```
+#[derive(Debug, Default)]
+pub struct BlakeoutPool {
+    hashers: Mutex<Vec<Blakeout>>,
+}
+
+impl BlakeoutPool {
+    /// Creates an empty pool, hashers are created on demand
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hashes `data` with a hasher from the pool and returns the result,
+    /// the same as `Blakeout::digest(data)`
+    pub fn hash(&self, data: impl AsRef<[u8]>) -> [u8; DEFAULT_HASH_SIZE] {
+        let mut hasher = self.check_out();
+        hasher.update(data);
+        let result = hasher.finalize_reset();
+        self.check_in(hasher);
+        result
+    }
+
+    /// Returns the number of idle hashers in the pool
+    pub fn idle(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn check_out(&self) -> Blakeout {
+        // The lock is released before hashing, so threads don't wait for each other
+        let hasher = self.lock().pop();
+        hasher.unwrap_or_default()
+    }
+
+    fn check_in(&self, mut hasher: Blakeout) {
+        hasher.reset();
+        self.lock().push(hasher);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Blakeout>> {
+        // Hashers are reset before returning to the pool, so a panic can't leave them broken
+        self.hashers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+thread_local! {
+    static HASHER: RefCell<Blakeout> = RefCell::new(Blakeout::new());
+}
+
+/// Hashes `data` with the hasher of the current thread and returns the result,
+/// the same as `Blakeout::digest(data)`.
+///
+/// It is a lighter alternative to `BlakeoutPool` for threads hashing one value at a time:
+/// the hasher is reset between calls, so its scratchpad is allocated once per thread.
+/// Keep in mind that the 2 MB scratchpad is retained until the thread exits.
+pub fn thread_local_hash(data: impl AsRef<[u8]>) -> [u8; DEFAULT_HASH_SIZE] {
+    HASHER.with_borrow_mut(|hasher| {
+        hasher.update(data);
+        hasher.finalize_reset()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+    use std::vec::Vec;
+    use crate::{Blakeout, BlakeoutPool, thread_local_hash};
+
+    #[test]
+    fn reuse() {
+        let pool = BlakeoutPool::new();
+        assert_eq!(0, pool.idle());
+        assert_eq!(Blakeout::digest(b"one"), pool.hash(b"one"));
+        assert_eq!(Blakeout::digest(b"two"), pool.hash(b"two"));
+        assert_eq!(1, pool.idle());
+    }
+
+    #[test]
+    fn thread_local() {
+        let capacity = || super::HASHER.with_borrow(|hasher| hasher.buffer.capacity());
+        assert_eq!(Blakeout::digest(b"one"), thread_local_hash(b"one"));
+        let first = capacity();
+        assert_eq!(32 * 65536, first);
+        assert_eq!(Blakeout::digest(b"two"), thread_local_hash(b"two"));
+        assert_eq!(Blakeout::digest(b"one"), thread_local_hash(b"one"));
+        assert_eq!(first, capacity());
+    }
+
+    #[test]
+    fn threads() {
+        let pool = Arc::new(BlakeoutPool::new());
+        let handles: Vec<_> = (0..8u8)
+            .map(|i| {
+                let pool = Arc::clone(&pool);
+                thread::spawn(move || {
+                    for j in 0..4u8 {
+                        let data = [i, j];
+                        assert_eq!(Blakeout::digest(data), pool.hash(data));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(pool.idle() >= 1 && pool.idle() <= 8);
+    }
+}
@@ -0,0 +1,122 @@
+/// Size of vector lanes used by SIMD implementations
+#[cfg(any(all(feature = "simd", target_arch = "x86_64", any(feature = "std", target_feature = "avx2")), all(feature = "simd", target_arch = "aarch64")))]
+const LANE_SIZE: usize = 32;
+
+/// Copies `from` to `to` in reverse order, they must have the same length
+#[allow(unreachable_code)]
+pub(crate) fn reverse_into(to: &mut [u8], from: &[u8]) {
+    debug_assert_eq!(to.len(), from.len());
+    #[cfg(all(feature = "simd", target_arch = "x86_64", feature = "std"))]
+    {
+        if std::is_x86_feature_detected!("avx2") {
+            // Safe as AVX2 is supported by the processor
+            return unsafe { reverse_avx2(to, from) };
+        }
+    }
+    #[cfg(all(feature = "simd", target_arch = "x86_64", not(feature = "std"), target_feature = "avx2"))]
+    {
+        return unsafe { reverse_avx2(to, from) };
+    }
+    #[cfg(all(feature = "simd", target_arch = "aarch64"))]
+    {
+        // NEON is always present on aarch64, so no runtime detection is needed
+        return unsafe { reverse_neon(to, from) };
+    }
+    reverse_scalar(to, from)
+}
+
+fn reverse_scalar(to: &mut [u8], from: &[u8]) {
+    for (to, from) in to.iter_mut().zip(from.iter().rev()) {
+        *to = *from;
+    }
+}
+
+/// Reverses 32 byte lanes from the end of `from` to the start of `to`, the rest is copied by `reverse_scalar`
+#[cfg(all(feature = "simd", target_arch = "x86_64", any(feature = "std", target_feature = "avx2")))]
+#[target_feature(enable = "avx2")]
+unsafe fn reverse_avx2(to: &mut [u8], from: &[u8]) {
+    use core::arch::x86_64::{__m256i, _mm256_loadu_si256, _mm256_permute4x64_epi64, _mm256_setr_epi8, _mm256_shuffle_epi8, _mm256_storeu_si256};
+
+    let len = to.len().min(from.len());
+    let lanes = len / LANE_SIZE;
+    // Reverses bytes in both 128 bit halves, then the halves are swapped
+    let mask = _mm256_setr_epi8(15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    for lane in 0..lanes {
+        let source = from.as_ptr().add(len - (lane + 1) * LANE_SIZE) as *const __m256i;
+        let value = _mm256_shuffle_epi8(_mm256_loadu_si256(source), mask);
+        let value = _mm256_permute4x64_epi64::<0b01_00_11_10>(value);
+        _mm256_storeu_si256(to.as_mut_ptr().add(lane * LANE_SIZE) as *mut __m256i, value);
+    }
+    let rest = len - lanes * LANE_SIZE;
+    reverse_scalar(&mut to[lanes * LANE_SIZE..len], &from[..rest]);
+}
+
+/// Reverses 32 byte lanes from the end of `from` to the start of `to`, the rest is copied by `reverse_scalar`
+#[cfg(all(feature = "simd", target_arch = "aarch64"))]
+unsafe fn reverse_neon(to: &mut [u8], from: &[u8]) {
+    use core::arch::aarch64::{uint8x16_t, vextq_u8, vld1q_u8, vrev64q_u8, vst1q_u8};
+
+    // Reverses bytes in both 64 bit halves, then the halves are swapped
+    unsafe fn reverse(value: uint8x16_t) -> uint8x16_t {
+        let value = vrev64q_u8(value);
+        vextq_u8::<8>(value, value)
+    }
+
+    let len = to.len().min(from.len());
+    let lanes = len / LANE_SIZE;
+    for lane in 0..lanes {
+        let source = from.as_ptr().add(len - (lane + 1) * LANE_SIZE);
+        let high = reverse(vld1q_u8(source.add(16)));
+        let low = reverse(vld1q_u8(source));
+        let target = to.as_mut_ptr().add(lane * LANE_SIZE);
+        vst1q_u8(target, high);
+        vst1q_u8(target.add(16), low);
+    }
+    let rest = len - lanes * LANE_SIZE;
+    reverse_scalar(&mut to[lanes * LANE_SIZE..len], &from[..rest]);
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use super::{reverse_into, reverse_scalar};
+    use crate::xorshift::XorShift;
+
+    /// Returns pseudo random bytes of `len` generated by xorshift from `seed`
+    fn random(len: usize, seed: u64) -> Vec<u8> {
+        let mut bytes = vec![0u8; len];
+        XorShift(seed).fill(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn matches_scalar() {
+        for (i, &len) in [0, 1, 15, 31, 32, 33, 64, 100, 1000, 1024].iter().enumerate() {
+            let from = random(len, i as u64 + 1);
+            let mut expected = vec![0u8; len];
+            reverse_scalar(&mut expected, &from);
+            let mut reversed: Vec<u8> = from.iter().rev().copied().collect();
+            assert_eq!(reversed, expected, "scalar for length {}", len);
+            reversed.iter_mut().for_each(|x| *x = 0);
+            reverse_into(&mut reversed, &from);
+            assert_eq!(expected, reversed, "length {}", len);
+        }
+    }
+
+    #[cfg(all(feature = "simd", target_arch = "x86_64", feature = "std"))]
+    #[test]
+    fn avx2_matches_scalar() {
+        if !std::is_x86_feature_detected!("avx2") {
+            return;
+        }
+        for len in 0..200 {
+            let from = random(len, len as u64 + 1);
+            let mut expected = vec![0u8; len];
+            reverse_scalar(&mut expected, &from);
+            let mut reversed = vec![0u8; len];
+            unsafe { super::reverse_avx2(&mut reversed, &from) };
+            assert_eq!(expected, reversed, "length {}", len);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use blake2::VarBlake2s;
+
+use crate::{BlakeoutMode, DEFAULT_HASH_SIZE, DEFAULT_LOOKBACK_BLOCKS, InnerDigest, MIN_HASH_COUNT, hash_scratchpad};
+
+/// Blakeout hasher with the scratchpad of `HASH_COUNT` hashes inside, without heap allocations.
+///
+/// It is meant for embedded targets without heap, it can be placed in a static or on the stack
+/// if it is big enough. The results are the same as of `Blakeout::with_params(32, HASH_COUNT)`.
+/// Small `HASH_COUNT` makes the hash much less memory hard, the default one for `Blakeout`
+/// is 65536 (2 MB).
+///
+/// The message is not accumulated, so the memory hard hashing runs only by `finalize`.
+///
+/// ```rust
+/// use blakeout::{Blakeout, BlakeoutStatic};
+///
+/// let mut hasher = BlakeoutStatic::<1024>::new();
+/// hasher.update(b"hello world");
+/// let mut expected = Blakeout::with_params(32, 1024).unwrap();
+/// expected.update(b"hello world");
+/// assert_eq!(expected.finalize(), hasher.finalize());
+/// ```
+///
+/// `HASH_COUNT` must be at least 2, it is checked at compile time:
+///
+/// ```compile_fail
+/// let hasher = blakeout::BlakeoutStatic::<1>::new();
+/// ```
+#[derive(Clone)]
+pub struct BlakeoutStatic<const HASH_COUNT: usize> {
+    buffer: [[u8; DEFAULT_HASH_SIZE]; HASH_COUNT],
+    digest: VarBlake2s,
+}
+
+impl<const HASH_COUNT: usize> BlakeoutStatic<HASH_COUNT> {
+    /// Fails the build if `HASH_COUNT` is too small to build a scratchpad
+    const VALID_COUNT: () = assert!(HASH_COUNT >= MIN_HASH_COUNT, "hash count must be at least 2");
+
+    /// Creates new hasher
+    pub fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::VALID_COUNT;
+        BlakeoutStatic {
+            buffer: [[0u8; DEFAULT_HASH_SIZE]; HASH_COUNT],
+            digest: VarBlake2s::new(DEFAULT_HASH_SIZE, &[], &[], &[]),
+        }
+    }
+
+    /// Appends supplied data to the message
+    pub fn update(&mut self, data: impl AsRef<[u8]>) {
+        self.digest.update(data.as_ref());
+    }
+
+    /// Consumes the hasher and returns result hash
+    pub fn finalize(mut self) -> [u8; DEFAULT_HASH_SIZE] {
+        self.finalize_reset()
+    }
+
+    /// Returns result hash and resets the hasher for reuse
+    pub fn finalize_reset(&mut self) -> [u8; DEFAULT_HASH_SIZE] {
+        let mut result = [0u8; DEFAULT_HASH_SIZE];
+        hash_scratchpad(&mut self.digest, self.buffer.as_flattened_mut(), DEFAULT_HASH_SIZE, DEFAULT_LOOKBACK_BLOCKS, BlakeoutMode::DualPass, &mut result);
+        result
+    }
+}
+
+/// Wipes the scratchpad and replaces the inner hasher holding the unhashed end of the message
+#[cfg(feature = "zeroize")]
+impl<const HASH_COUNT: usize> Drop for BlakeoutStatic<HASH_COUNT> {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+        self.buffer.zeroize();
+        self.digest = VarBlake2s::new(DEFAULT_HASH_SIZE, &[], &[], &[]);
+    }
+}
+
+impl<const HASH_COUNT: usize> Default for BlakeoutStatic<HASH_COUNT> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Blakeout, BlakeoutStatic, to_hex};
+    const DATA: &[u8; 29] = b"Science is poetry of reality!";
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn wiped_on_drop() {
+        let mut digest = core::mem::ManuallyDrop::new(BlakeoutStatic::<1024>::new());
+        digest.update(DATA);
+        digest.finalize_reset();
+        assert!(digest.buffer.iter().flatten().any(|x| *x != 0));
+        // The memory stays ours after the drop, so the wiping can be seen
+        unsafe { core::ptr::drop_in_place(&mut *digest) };
+        assert!(digest.buffer.iter().flatten().all(|x| *x == 0));
+    }
+
+    #[test]
+    fn static_hasher() {
+        let mut digest = BlakeoutStatic::<1024>::new();
+        digest.update(&DATA[..10]);
+        digest.update(&DATA[10..]);
+        let result = digest.finalize_reset();
+        assert_eq!("0c3172bd535d7e158a2d245037b8a9fb0357f6ec9e89701aa8cf00586408c574", to_hex(&result));
+        let mut expected = Blakeout::with_params(32, 1024).unwrap();
+        expected.update(DATA);
+        expected.compute();
+        assert_eq!(expected, result);
+
+        digest.update(DATA);
+        assert_eq!(result, digest.finalize());
+    }
+}
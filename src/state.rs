@@ -0,0 +1,198 @@
+use alloc::vec::Vec;
+use core::convert::TryInto;
+
+use crate::{Blakeout, BlakeoutBuilder, BlakeoutMode, Error, InnerDigest};
+
+/// Prefix of every saved state
+const MAGIC: &[u8; 4] = b"BLKO";
+/// Version of saved state format, must be changed with any change of the format
+const VERSION: u8 = 5;
+
+/// Saved state of a hasher, that can be stored and restored later to continue hashing.
+///
+/// It contains the parameters, the key, the domain, the accumulated message and the last result,
+/// so it must be kept as secret as the message and the key. The scratchpad is not saved,
+/// it is regenerated by the next hashing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateBlob(Vec<u8>);
+
+impl StateBlob {
+    /// Returns raw bytes of the state to store them
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Creates the state from stored bytes, they are checked by `Blakeout::restore_state`
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        StateBlob(bytes.to_vec())
+    }
+}
+
+impl Blakeout {
+    /// Creates a hasher from the state saved by `save_state`, the parameters are validated
+    /// as by the builder. Returns `Error::InvalidState` if the state is malformed,
+    /// saved by incompatible version or for another inner hash function.
+    pub fn restore_state(blob: StateBlob) -> Result<Self, Error> {
+        Self::restore_state_inner(blob)
+    }
+}
+
+impl<D: InnerDigest> Blakeout<D> {
+    /// Saves the state of the hasher to continue hashing later by `restore_state`
+    pub fn save_state(&self) -> StateBlob {
+        let mut blob = Vec::with_capacity(64 + self.input.len());
+        blob.extend_from_slice(MAGIC);
+        blob.push(VERSION);
+        blob.push(D::ID);
+        blob.push(self.dirty as u8);
+        blob.extend_from_slice(&(self.hash_size as u64).to_le_bytes());
+        blob.extend_from_slice(&(self.hash_count as u64).to_le_bytes());
+        blob.extend_from_slice(&(self.lookback as u64).to_le_bytes());
+        blob.push(self.mode as u8);
+        for field in &[&self.key[..], &self.salt, &self.personal, &self.domain, self.result(), &self.input] {
+            blob.extend_from_slice(&(field.len() as u64).to_le_bytes());
+            blob.extend_from_slice(field);
+        }
+        StateBlob(blob)
+    }
+
+    /// Same as `Blakeout::restore_state`, but for hasher with inner hash function `D`
+    pub fn restore_state_inner(blob: StateBlob) -> Result<Self, Error> {
+        let mut reader = Reader(blob.as_bytes());
+        if reader.take(MAGIC.len())? != MAGIC || reader.byte()? != VERSION {
+            return Err(Error::InvalidState);
+        }
+        if reader.byte()? != D::ID {
+            return Err(Error::InvalidState);
+        }
+        let dirty = match reader.byte()? {
+            0 => false,
+            1 => true,
+            _ => return Err(Error::InvalidState),
+        };
+        let hash_size = reader.size()?;
+        let hash_count = reader.size()?;
+        let lookback = reader.size()?;
+        let mode = match reader.byte()? {
+            0 => BlakeoutMode::DualPass,
+            1 => BlakeoutMode::ForwardOnly,
+            _ => return Err(Error::InvalidState),
+        };
+        let key = reader.field()?;
+        let salt = reader.field()?;
+        let personal = reader.field()?;
+        let domain = reader.field()?;
+        let result = reader.field()?;
+        let input = reader.field()?;
+        // The result is empty only if nothing was hashed yet
+        if !reader.0.is_empty() || (result.len() != hash_size && (dirty || !result.is_empty())) {
+            return Err(Error::InvalidState);
+        }
+
+        let mut digest = BlakeoutBuilder::new()
+            .hash_size(hash_size)
+            .hash_count(hash_count)
+            .lookback_blocks(lookback)
+            .mode(mode)
+            .key(key)
+            .salt(salt)
+            .personal(personal)
+            .domain(domain)
+            .build_inner::<D>()?;
+        digest.result[..result.len()].copy_from_slice(result);
+        digest.result_len = result.len();
+        digest.input = input.to_vec();
+        digest.dirty = dirty;
+        Ok(digest)
+    }
+}
+
+/// Reads the fields of saved state, returns `Error::InvalidState` if they are truncated
+pub(crate) struct Reader<'a>(pub(crate) &'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < len {
+            return Err(Error::InvalidState);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn size(&mut self) -> Result<usize, Error> {
+        let bytes = self.take(8)?.try_into().unwrap();
+        u64::from_le_bytes(bytes).try_into().map_err(|_| Error::InvalidState)
+    }
+
+    pub(crate) fn field(&mut self) -> Result<&'a [u8], Error> {
+        let len = self.size()?;
+        self.take(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Blakeout, Blakeout2b, BlakeoutBuilder, BlakeoutMode, Error, StateBlob};
+    const DATA: &[u8; 29] = b"Science is poetry of reality!";
+
+    #[test]
+    fn save_and_restore() {
+        let mut digest = Blakeout::new();
+        digest.update(&DATA[..10]);
+        digest.compute();
+        let blob = digest.save_state();
+        let mut restored = Blakeout::restore_state(StateBlob::from_bytes(blob.as_bytes())).unwrap();
+        assert_eq!(digest.result(), restored.result());
+        restored.update(&DATA[10..]);
+        // Not hashed updates are saved as the message
+        let pending = Blakeout::restore_state(restored.save_state()).unwrap();
+        assert_eq!(Blakeout::digest(DATA), pending.finalize());
+        assert_eq!(Blakeout::digest(DATA), restored.finalize());
+
+        let mut digest = BlakeoutBuilder::new().hash_count(1024).lookback_blocks(3).mode(BlakeoutMode::ForwardOnly).key(b"key").personal(b"person").domain(b"domain").build().unwrap();
+        let fresh = digest.clone();
+        let mut restored = Blakeout::restore_state(fresh.save_state()).unwrap();
+        digest.update(DATA);
+        digest.compute();
+        restored.update(DATA);
+        restored.compute();
+        assert_ne!(&Blakeout::digest(DATA)[..], restored.result());
+        assert_eq!(digest.finalize(), restored.finalize());
+    }
+
+    #[test]
+    fn chained() {
+        let mut digest = Blakeout::new();
+        digest.update_chained(&DATA[..10]);
+        let mut restored = Blakeout::restore_state(digest.save_state()).unwrap();
+        digest.update_chained(&DATA[10..]);
+        restored.update_chained(&DATA[10..]);
+        assert_eq!(digest.result(), restored.result());
+    }
+
+    #[test]
+    fn invalid() {
+        let blob = Blakeout::new().save_state();
+        let bytes = blob.as_bytes();
+        assert_eq!(Some(Error::InvalidState), Blakeout::restore_state(StateBlob::from_bytes(&bytes[1..])).err());
+        assert_eq!(Some(Error::InvalidState), Blakeout::restore_state(StateBlob::from_bytes(&bytes[..bytes.len() - 1])).err());
+        assert_eq!(Some(Error::InvalidState), Blakeout2b::restore_state_inner(blob.clone()).err());
+        #[cfg(feature = "blake3")]
+        {
+            let blake3 = crate::Blakeout3::new_inner().save_state();
+            assert_eq!(Some(Error::InvalidState), Blakeout::restore_state(blake3.clone()).err());
+            assert!(crate::Blakeout3::restore_state_inner(blake3).is_ok());
+        }
+        let mut version = bytes.to_vec();
+        version[4] += 1;
+        assert_eq!(Some(Error::InvalidState), Blakeout::restore_state(StateBlob::from_bytes(&version)).err());
+        let mut size = bytes.to_vec();
+        size[7] = 33;
+        assert_eq!(Some(Error::InvalidHashSize(33)), Blakeout::restore_state(StateBlob::from_bytes(&size)).err());
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{Blakeout, DEFAULT_HASH_SIZE};
+
+/// Derives key material from `password` and `salt` by `rounds` chained Blakeout hashes
+/// with default parameters.
+///
+/// The construction is:
+///
+/// ```text
+/// h[1] = Blakeout(le64(salt.len()) || salt || password)
+/// h[i] = Blakeout(h[i - 1] || password), for i in 2..=rounds
+/// ```
+///
+/// where `le64` is 64 bit little-endian number, the result is `h[rounds]`.
+/// Zero `rounds` is the same as one. Every round costs a full memory hard hashing,
+/// use a unique random salt for every password.
+pub fn stretch(password: &[u8], salt: &[u8], rounds: usize) -> [u8; DEFAULT_HASH_SIZE] {
+    let mut hasher = Blakeout::new();
+    hasher.update_all([&(salt.len() as u64).to_le_bytes()[..], salt, password]);
+    for _ in 1..rounds {
+        hasher.chain(password);
+    }
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Blakeout, stretch};
+
+    #[test]
+    fn construction() {
+        let mut first = [0u8; 8].to_vec();
+        first[0] = 4;
+        first.extend_from_slice(b"saltpassword");
+        let first = Blakeout::digest(&first);
+        assert_eq!(first, stretch(b"password", b"salt", 1));
+        assert_eq!(first, stretch(b"password", b"salt", 0));
+        let second = Blakeout::digest([&first[..], b"password"].concat());
+        assert_eq!(second, stretch(b"password", b"salt", 2));
+    }
+
+    #[test]
+    fn diverges() {
+        let key = stretch(b"password", b"salt", 3);
+        assert_eq!(key, stretch(b"password", b"salt", 3));
+        assert_ne!(key, stretch(b"password", b"pepper", 3));
+        assert_ne!(key, stretch(b"password", b"salt", 4));
+        assert_ne!(key, stretch(b"passwore", b"salt", 3));
+        // Salt is length prefixed, so moving bytes between salt and password changes the key
+        assert_ne!(stretch(b"word", b"saltpass", 1), stretch(b"password", b"salt", 1));
+    }
+}
@@ -0,0 +1,58 @@
+//! Statistical diagnostics, enabled by `testutil` feature.
+//!
+//! They are not a part of the algorithm, but help to evaluate whether reduced parameters
+//! still behave like a good hash.
+
+use crate::{Blakeout, DEFAULT_HASH_COUNT, DEFAULT_HASH_SIZE};
+use crate::xorshift::XorShift;
+
+/// Size of random inputs hashed by `avalanche`
+const INPUT_SIZE: usize = 32;
+
+/// Measures avalanche effect of Blakeout with default parameters, see `avalanche_for`
+pub fn avalanche(samples: usize) -> f64 {
+    avalanche_for(samples, DEFAULT_HASH_COUNT)
+}
+
+/// Measures avalanche effect of Blakeout with 32 byte hashes and `hash_count` hashes
+/// in the scratchpad.
+///
+/// For every sample it hashes a pseudo random input and the same input with one flipped bit,
+/// and returns the average fraction of result bits that changed. A good hash gives a ratio
+/// near 0.5. The inputs are the same on every call, so the result is reproducible.
+/// Every sample costs two hashes, panics if `hash_count` is invalid.
+pub fn avalanche_for(samples: usize, hash_count: usize) -> f64 {
+    let mut hasher = Blakeout::with_params(DEFAULT_HASH_SIZE, hash_count).expect("invalid hash count");
+    let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+    let mut changed = 0u64;
+    for _ in 0..samples {
+        let mut input = [0u8; INPUT_SIZE];
+        rng.fill(&mut input);
+        hasher.update(input);
+        let first = hasher.finalize_reset();
+        let bit = rng.0 as usize % (INPUT_SIZE * 8);
+        input[bit / 8] ^= 1 << (bit % 8);
+        hasher.update(input);
+        let second = hasher.finalize_reset();
+        changed += first.iter().zip(second.iter()).map(|(a, b)| (a ^ b).count_ones() as u64).sum::<u64>();
+    }
+    changed as f64 / (samples.max(1) * DEFAULT_HASH_SIZE * 8) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{avalanche, avalanche_for};
+
+    #[test]
+    fn default_params() {
+        let ratio = avalanche(16);
+        assert!(ratio > 0.45 && ratio < 0.55, "avalanche ratio {}", ratio);
+    }
+
+    #[test]
+    fn small_scratchpad() {
+        let ratio = avalanche_for(64, 16);
+        assert!(ratio > 0.45 && ratio < 0.55, "avalanche ratio {}", ratio);
+        assert_eq!(0.0, avalanche_for(0, 16));
+    }
+}
@@ -0,0 +1,63 @@
+//! WebAssembly bindings, enabled by `wasm` feature.
+//!
+//! Every hasher allocates its scratchpad (2 MB with default parameters) in wasm linear
+//! memory on first hashing and keeps it until it is freed, so create few of them
+//! and call `free()` from JavaScript when done.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Blakeout, to_hex};
+
+/// Blakeout hasher exported to JavaScript
+#[wasm_bindgen]
+pub struct WasmBlakeout {
+    inner: Blakeout,
+}
+
+impl Default for WasmBlakeout {
+    fn default() -> Self {
+        WasmBlakeout::new()
+    }
+}
+
+#[wasm_bindgen]
+impl WasmBlakeout {
+    /// Creates new hasher with default parameters
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmBlakeout {
+        WasmBlakeout { inner: Blakeout::new() }
+    }
+
+    /// Appends supplied data to the message
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Hashes the message and returns result hash as HEX string,
+    /// the hash of an empty message if nothing was supplied
+    #[wasm_bindgen(js_name = finalizeHex)]
+    pub fn finalize_hex(&mut self) -> String {
+        to_hex(self.inner.compute())
+    }
+}
+
+/// Hashes supplied data with default parameters and returns the result as HEX string
+#[wasm_bindgen(js_name = blakeoutHex)]
+pub fn blakeout_hex(data: &[u8]) -> String {
+    Blakeout::digest_hex(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const DATA: &[u8; 29] = b"Science is poetry of reality!";
+
+    #[test]
+    fn bindings() {
+        let mut hasher = WasmBlakeout::new();
+        assert_eq!(Blakeout::digest_hex(b""), hasher.finalize_hex());
+        hasher.update(DATA);
+        assert_eq!(Blakeout::digest_hex(DATA), hasher.finalize_hex());
+        assert_eq!(Blakeout::digest_hex(DATA), blakeout_hex(DATA));
+    }
+}
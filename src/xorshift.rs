@@ -0,0 +1,20 @@
+/// Xorshift generator of reproducible pseudo random numbers for tests and diagnostics,
+/// it is fast, but not random enough for anything else. The state must not be zero.
+pub(crate) struct XorShift(pub(crate) u64);
+
+impl XorShift {
+    /// Advances the state and returns it
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Fills `bytes` by the lowest bytes of next numbers
+    pub(crate) fn fill(&mut self, bytes: &mut [u8]) {
+        for byte in bytes.iter_mut() {
+            *byte = self.next_u64() as u8;
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! Run by `wasm-pack test --headless --firefox -- --features wasm`
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use wasm_bindgen_test::*;
+use blakeout::wasm::{WasmBlakeout, blakeout_hex};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const DATA: &[u8; 29] = b"Science is poetry of reality!";
+const HASH: &str = "4be892daff5d5432b43bf05c9d2ea4769daf2dd1ec482c23839ce5d6950e9e62";
+
+#[wasm_bindgen_test]
+fn matches_native() {
+    assert_eq!(HASH, blakeout_hex(DATA));
+    let mut hasher = WasmBlakeout::new();
+    hasher.update(DATA);
+    assert_eq!(HASH, hasher.finalize_hex());
+}
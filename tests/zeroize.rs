@@ -0,0 +1,43 @@
+#![cfg(feature = "zeroize")]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, Ordering};
+use blakeout::Blakeout;
+
+const HASH_SIZE: usize = 32;
+/// Unusual count to tell the scratchpad apart from other allocations
+const HASH_COUNT: usize = 4099;
+
+static FREED: AtomicBool = AtomicBool::new(false);
+static WIPED: AtomicBool = AtomicBool::new(false);
+
+/// Checks the contents of the scratchpad right before giving the memory back
+struct CheckingAllocator;
+
+unsafe impl GlobalAlloc for CheckingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if layout.size() == HASH_SIZE * HASH_COUNT {
+            let memory = std::slice::from_raw_parts(ptr, layout.size());
+            WIPED.store(memory.iter().all(|x| *x == 0), Ordering::SeqCst);
+            FREED.store(true, Ordering::SeqCst);
+        }
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CheckingAllocator = CheckingAllocator;
+
+#[test]
+fn scratchpad_wiped_on_drop() {
+    let mut digest = Blakeout::with_params(HASH_SIZE, HASH_COUNT).unwrap();
+    digest.update(b"Science is poetry of reality!");
+    digest.compute();
+    drop(digest);
+    assert!(FREED.load(Ordering::SeqCst));
+    assert!(WIPED.load(Ordering::SeqCst));
+}